@@ -0,0 +1,128 @@
+//! Command-line parsing shared by the binaries built on the library
+//!
+//! Each binary matches the options it knows itself, and reads their values through [`ArgParser`].
+
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::ops::RangeInclusive;
+use std::str::FromStr;
+
+/// Goes through command-line arguments one option at a time
+///
+/// Option values can be given either as `--option value` or as `--option=value`.
+pub struct ArgParser<I> {
+    args: I,
+    /// The option whose value is being read
+    option: String,
+    /// The value given as `--option=value`, until it's read
+    inline_value: Option<String>,
+}
+
+impl<I: Iterator<Item = String>> ArgParser<I> {
+    /// Parses the arguments, not including the program name
+    pub fn new(args: impl IntoIterator<IntoIter = I>) -> Self {
+        Self {
+            args: args.into_iter(),
+            option: String::new(),
+            inline_value: None,
+        }
+    }
+
+    /// The next option, or `None` once there are no arguments left
+    ///
+    /// Fails if the previous option was given a value it doesn't take.
+    pub fn next_option(&mut self) -> Result<Option<String>, ArgsError> {
+        if self.inline_value.is_some() {
+            return Err(ArgsError::UnexpectedValue(self.option.clone()));
+        }
+
+        let Some(arg) = self.args.next() else {
+            return Ok(None);
+        };
+
+        (self.option, self.inline_value) = match arg.split_once('=') {
+            Some((option, value)) if option.starts_with("--") => {
+                (option.to_string(), Some(value.to_string()))
+            }
+
+            _ => (arg, None),
+        };
+
+        Ok(Some(self.option.clone()))
+    }
+
+    /// The value of the current option
+    pub fn value(&mut self) -> Result<String, ArgsError> {
+        self.inline_value
+            .take()
+            .or_else(|| self.args.next())
+            .ok_or_else(|| ArgsError::MissingValue(self.option.clone()))
+    }
+
+    pub fn parsed_value<T: FromStr>(&mut self) -> Result<T, ArgsError> {
+        let value = self.value()?;
+
+        value.parse().map_err(|_| self.invalid_value(value))
+    }
+
+    /// The value of the current option, which has to be within `range`
+    pub fn value_in<T: FromStr + PartialOrd>(
+        &mut self,
+        range: RangeInclusive<T>,
+    ) -> Result<T, ArgsError> {
+        let value = self.value()?;
+
+        value
+            .parse()
+            .ok()
+            .filter(|parsed| range.contains(parsed))
+            .ok_or_else(|| self.invalid_value(value))
+    }
+
+    /// The value of the current option, looked up by name like [`Topology::from_name`]
+    ///
+    /// [`Topology::from_name`]: crate::maze::Topology::from_name
+    pub fn named_value<T>(
+        &mut self,
+        from_name: impl FnOnce(&str) -> Option<T>,
+    ) -> Result<T, ArgsError> {
+        let value = self.value()?;
+
+        from_name(&value).ok_or_else(|| self.invalid_value(value))
+    }
+
+    /// An error for a value of the current option that it doesn't accept
+    pub fn invalid_value(&self, value: String) -> ArgsError {
+        ArgsError::InvalidValue {
+            option: self.option.clone(),
+            value,
+        }
+    }
+
+    pub fn unknown_option(&self) -> ArgsError {
+        ArgsError::UnknownOption(self.option.clone())
+    }
+}
+
+#[derive(Debug)]
+pub enum ArgsError {
+    UnknownOption(String),
+    MissingValue(String),
+    UnexpectedValue(String),
+    InvalidValue { option: String, value: String },
+}
+
+impl Error for ArgsError {}
+
+impl Display for ArgsError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UnknownOption(option) => write!(f, "Unknown option '{}'", option),
+            Self::MissingValue(option) => write!(f, "Missing value for '{}'", option),
+            Self::UnexpectedValue(option) => write!(f, "'{}' doesn't take a value", option),
+            Self::InvalidValue { option, value } => {
+                write!(f, "Invalid value '{}' for '{}'", value, option)
+            }
+        }
+    }
+}
@@ -0,0 +1,350 @@
+//! Generates a maze without opening a window, and writes it to stdout or a file
+
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use maze_gen::args::{ArgParser, ArgsError};
+use maze_gen::image::Image;
+use maze_gen::import::{self, Grid};
+use maze_gen::mask::Mask;
+use maze_gen::maze::{Algorithm, Maze, Topology};
+use maze_gen::render::{self, RenderOptions};
+use maze_gen::save;
+use maze_gen::share;
+use maze_gen::text::{self, TextStyle};
+use maze_gen::tiles::{TileMap, TileOptions};
+
+const MAZE_WIDTH: i64 = 16;
+const MAZE_HEIGHT: i64 = 16;
+/// The widest or highest maze that can be generated, which keeps a mistyped size
+/// from trying to fill all the memory there is
+const MAZE_MAX_SIZE: i64 = 1024;
+const MAZE_TOPOLOGY: Topology = Topology::Orthogonal;
+const MAZE_ALGORITHM: Algorithm = Algorithm::RecursiveBacktracker;
+/// The size of a cell in a png, in pixels
+const PNG_CELL_SIZE: i64 = 16;
+
+fn main() {
+    let args = match Args::parse(env::args().skip(1)) {
+        Ok(args) => args,
+
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, Args::usage());
+            process::exit(2);
+        }
+    };
+
+    if args.help {
+        print!("{}", Args::usage());
+        return;
+    }
+
+    if let Err(e) = run(args) {
+        eprintln!("{}", e);
+        process::exit(1);
+    }
+}
+
+fn run(args: Args) -> Result<(), Box<dyn Error>> {
+    let seed = args.seed.unwrap_or_else(rand::random);
+    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+
+    let maze = match &args.import {
+        Some(import_path) => import_maze(&args, import_path)
+            .map_err(|e| format!("{}: {}", import_path.display(), e))?,
+
+        None => generate_maze(&args, &mut rng)?,
+    };
+
+    let output = match args.format {
+        Format::Ascii => text::render(&maze, TextStyle::Ascii).into_bytes(),
+        Format::Unicode => text::render(&maze, TextStyle::Unicode).into_bytes(),
+        Format::Json => save::to_json(&maze, seed, &rng).into_bytes(),
+        Format::Code => (share::to_code(&maze)? + "\n").into_bytes(),
+        Format::Csv => TileMap::new(&maze, args.tile_options).to_csv().into_bytes(),
+        Format::Tmx => TileMap::new(&maze, args.tile_options).to_tmx().into_bytes(),
+        Format::Tmj => TileMap::new(&maze, args.tile_options)
+            .to_tiled_json()
+            .into_bytes(),
+        Format::Png => {
+            let options = RenderOptions {
+                cell_size: args.cell_size.unwrap_or(PNG_CELL_SIZE),
+                ..RenderOptions::default()
+            };
+
+            let mut png = Vec::new();
+            render::render(&maze, options).write_png(&mut png)?;
+
+            png
+        }
+    };
+
+    match &args.output {
+        Some(path) => fs::write(path, output).map_err(|e| format!("{}: {}", path.display(), e))?,
+        None => io::stdout().write_all(&output)?,
+    }
+
+    Ok(())
+}
+
+fn generate_maze(args: &Args, rng: &mut ChaCha8Rng) -> Result<Maze, Box<dyn Error>> {
+    let mask = match &args.mask {
+        Some(mask_path) => {
+            let mask = Mask::load(mask_path)
+                .and_then(|mask| mask.within_size(MAZE_MAX_SIZE))
+                .map_err(|e| format!("{}: {}", mask_path.display(), e))?;
+
+            match (args.maze_width, args.maze_height) {
+                (None, None) => mask,
+
+                (width, height) => mask.resized(
+                    width.unwrap_or(mask.width()),
+                    height.unwrap_or(mask.height()),
+                )?,
+            }
+        }
+
+        None => Mask::full(
+            args.maze_width.unwrap_or(MAZE_WIDTH),
+            args.maze_height.unwrap_or(MAZE_HEIGHT),
+        ),
+    };
+
+    let mut maze = Maze::with_mask(
+        mask,
+        args.topology.unwrap_or(MAZE_TOPOLOGY),
+        args.algorithm.unwrap_or(MAZE_ALGORITHM),
+    );
+
+    maze.finish(rng);
+
+    Ok(maze)
+}
+
+/// Reads a maze from an image with cells of a known size, filling the image unless a size is given
+fn import_maze(args: &Args, import_path: &Path) -> Result<Maze, Box<dyn Error>> {
+    let image = Image::load_png(import_path)?;
+
+    let cell_size = args.cell_size.ok_or("--import needs a --cell-size")?;
+    let (x, y) = args.grid_offset.unwrap_or((0, 0));
+
+    let grid = Grid::with_cell_size(
+        x,
+        y,
+        cell_size,
+        args.maze_width
+            .unwrap_or((image.width() - 1 - x) / cell_size),
+        args.maze_height
+            .unwrap_or((image.height() - 1 - y) / cell_size),
+    );
+
+    Ok(import::from_image(&image, &grid)?)
+}
+
+#[derive(Clone, Copy)]
+enum Format {
+    Ascii,
+    Unicode,
+    Json,
+    Code,
+    Csv,
+    /// Tiled's XML map format
+    Tmx,
+    /// Tiled's JSON map format
+    Tmj,
+    /// An image drawn without SDL, like the app's exported ones but not pixel-identical to them
+    Png,
+}
+
+impl Format {
+    fn all() -> &'static [Format] {
+        &[
+            Format::Ascii,
+            Format::Unicode,
+            Format::Json,
+            Format::Code,
+            Format::Csv,
+            Format::Tmx,
+            Format::Tmj,
+            Format::Png,
+        ]
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Format::Ascii => "ascii",
+            Format::Unicode => "unicode",
+            Format::Json => "json",
+            Format::Code => "code",
+            Format::Csv => "csv",
+            Format::Tmx => "tmx",
+            Format::Tmj => "tmj",
+            Format::Png => "png",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::all()
+            .iter()
+            .copied()
+            .find(|format| format.name() == name)
+    }
+}
+
+/// Options given on the command line, left empty when not given
+struct Args {
+    maze_width: Option<i64>,
+    maze_height: Option<i64>,
+    mask: Option<PathBuf>,
+    topology: Option<Topology>,
+    algorithm: Option<Algorithm>,
+    seed: Option<u64>,
+    /// Read a maze from an image instead of generating one
+    import: Option<PathBuf>,
+    cell_size: Option<i64>,
+    /// Where the top left corner of the grid is in the imported image
+    grid_offset: Option<(i64, i64)>,
+    format: Format,
+    tile_options: TileOptions,
+    output: Option<PathBuf>,
+    help: bool,
+}
+
+impl Args {
+    /// Parses the arguments, not including the program name
+    fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, ArgsError> {
+        let mut args = ArgParser::new(args);
+        let mut parsed = Self {
+            maze_width: None,
+            maze_height: None,
+            mask: None,
+            topology: None,
+            algorithm: None,
+            seed: None,
+            import: None,
+            cell_size: None,
+            grid_offset: None,
+            format: Format::Ascii,
+            tile_options: TileOptions::default(),
+            output: None,
+            help: false,
+        };
+
+        while let Some(option) = args.next_option()? {
+            match option.as_str() {
+                "-W" | "--width" => {
+                    parsed.maze_width = Some(args.value_in(1..=MAZE_MAX_SIZE)?);
+                }
+
+                "-H" | "--height" => {
+                    parsed.maze_height = Some(args.value_in(1..=MAZE_MAX_SIZE)?);
+                }
+
+                "-m" | "--mask" => {
+                    parsed.mask = Some(PathBuf::from(args.value()?));
+                }
+
+                "-t" | "--topology" => {
+                    parsed.topology = Some(args.named_value(Topology::from_name)?);
+                }
+
+                "-a" | "--algorithm" => {
+                    parsed.algorithm = Some(args.named_value(Algorithm::from_name)?);
+                }
+
+                "-s" | "--seed" => {
+                    parsed.seed = Some(args.parsed_value()?);
+                }
+
+                "-i" | "--import" => {
+                    parsed.import = Some(PathBuf::from(args.value()?));
+                }
+
+                "--cell-size" => {
+                    parsed.cell_size = Some(args.value_in(1..=MAZE_MAX_SIZE)?);
+                }
+
+                "--grid-offset" => {
+                    let value = args.value()?;
+                    let offset = value
+                        .split_once(',')
+                        .and_then(|(x, y)| Some((x.parse().ok()?, y.parse().ok()?)))
+                        .filter(|&(x, y)| x >= 0 && y >= 0)
+                        .ok_or_else(|| args.invalid_value(value))?;
+
+                    parsed.grid_offset = Some(offset);
+                }
+
+                "-F" | "--format" => {
+                    parsed.format = args.named_value(Format::from_name)?;
+                }
+
+                "--corridor-width" => {
+                    parsed.tile_options.corridor_width = args.value_in(1..=u32::MAX)?;
+                }
+
+                "--wall-width" => {
+                    parsed.tile_options.wall_width = args.value_in(1..=u32::MAX)?;
+                }
+
+                "-o" | "--output" => {
+                    parsed.output = Some(PathBuf::from(args.value()?));
+                }
+
+                "-h" | "--help" => {
+                    parsed.help = true;
+                }
+
+                _ => return Err(args.unknown_option()),
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    fn usage() -> String {
+        let algorithms: Vec<&str> = Algorithm::all().iter().map(Algorithm::name).collect();
+        let topologies: Vec<&str> = Topology::all().iter().map(Topology::name).collect();
+        let formats: Vec<&str> = Format::all().iter().map(Format::name).collect();
+
+        format!(
+            "\
+Usage: maze-cli [OPTIONS]
+
+Options:
+  -W, --width <CELLS>        Maze width, up to {max} (default: {width}, or the mask's width)
+  -H, --height <CELLS>       Maze height, up to {max} (default: {height}, or the mask's height)
+  -m, --mask <FILE>          Shape the maze with an ASCII template or a black-and-white PNG
+  -t, --topology <NAME>      One of: {topologies}
+  -a, --algorithm <NAME>     One of: {algorithms}
+  -s, --seed <NUMBER>        Seed for a reproducible maze (random by default)
+  -i, --import <FILE>        Read an orthogonal maze from a PNG of its grid instead of generating one
+      --cell-size <PX>       The size of the imported maze's cells, or of the cells of a png,
+                             in pixels (default for png: {png_cell_size})
+      --grid-offset <X,Y>    Where the imported maze's top left corner is (default: 0,0)
+  -F, --format <NAME>        One of: {formats}
+      --corridor-width <N>   Corridor width in tiles for csv, tmx and tmj (default: 1)
+      --wall-width <N>       Wall width in tiles for csv, tmx and tmj (default: 1)
+  -o, --output <FILE>        Write to a file instead of stdout
+  -h, --help                 Print this help
+
+A png is drawn without SDL, in the app's default colors. It's drawn the same way as the app's
+exported images, but isn't pixel-identical to them: it has no antialiasing or padding.
+",
+            width = MAZE_WIDTH,
+            height = MAZE_HEIGHT,
+            max = MAZE_MAX_SIZE,
+            png_cell_size = PNG_CELL_SIZE,
+            topologies = topologies.join(", "),
+            algorithms = algorithms.join(", "),
+            formats = formats.join(", "),
+        )
+    }
+}
@@ -0,0 +1,170 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::fs::File;
+use std::io::{self, BufReader, Write};
+use std::path::Path;
+
+use png::{BitDepth, ColorType, Transformations};
+
+/// Pixels of an image as RGBA, whatever format they were decoded from
+pub struct Image {
+    width: i64,
+    height: i64,
+    pixels: Vec<[u8; 4]>,
+}
+
+impl Image {
+    /// An image of a single color, to draw on
+    pub fn new(width: i64, height: i64, color: [u8; 4]) -> Self {
+        Self {
+            width,
+            height,
+            pixels: vec![color; (width * height) as usize],
+        }
+    }
+
+    /// Decodes a PNG of any color type and bit depth
+    pub fn load_png(path: impl AsRef<Path>) -> Result<Self, ImageError> {
+        let file = File::open(path).map_err(ImageError::Io)?;
+
+        let mut decoder = png::Decoder::new(BufReader::new(file));
+        decoder.set_transformations(Transformations::normalize_to_color8());
+
+        let mut reader = decoder.read_info().map_err(ImageError::Png)?;
+        let mut buffer = vec![0; reader.output_buffer_size().ok_or(ImageError::TooLarge)?];
+        let info = reader.next_frame(&mut buffer).map_err(ImageError::Png)?;
+
+        let channels = match info.color_type {
+            ColorType::Grayscale => 1,
+            ColorType::GrayscaleAlpha => 2,
+            ColorType::Rgb => 3,
+            ColorType::Rgba => 4,
+            ColorType::Indexed => unreachable!("Indexed images are expanded by the decoder"),
+        };
+
+        let pixels = buffer[..info.buffer_size()]
+            .chunks_exact(info.line_size)
+            .flat_map(|row| row[..info.width as usize * channels].chunks_exact(channels))
+            .map(|pixel| match channels {
+                1 => [pixel[0], pixel[0], pixel[0], u8::MAX],
+                2 => [pixel[0], pixel[0], pixel[0], pixel[1]],
+                3 => [pixel[0], pixel[1], pixel[2], u8::MAX],
+                _ => [pixel[0], pixel[1], pixel[2], pixel[3]],
+            })
+            .collect();
+
+        Ok(Self {
+            width: info.width as i64,
+            height: info.height as i64,
+            pixels,
+        })
+    }
+
+    /// Encodes the image as an 8-bit RGBA PNG
+    pub fn write_png(&self, writer: impl Write) -> Result<(), png::EncodingError> {
+        let mut encoder = png::Encoder::new(writer, self.width as u32, self.height as u32);
+        encoder.set_color(ColorType::Rgba);
+        encoder.set_depth(BitDepth::Eight);
+
+        let mut writer = encoder.write_header()?;
+        writer.write_image_data(self.pixels.as_flattened())?;
+
+        writer.finish()
+    }
+
+    pub fn width(&self) -> i64 {
+        self.width
+    }
+
+    pub fn height(&self) -> i64 {
+        self.height
+    }
+
+    /// The pixel at a position, clamped to the edges of the image
+    pub fn pixel(&self, x: i64, y: i64) -> [u8; 4] {
+        let x = x.clamp(0, self.width - 1);
+        let y = y.clamp(0, self.height - 1);
+
+        self.pixels[(y * self.width + x) as usize]
+    }
+
+    /// Every pixel, row by row from the top
+    pub fn pixels(&self) -> &[[u8; 4]] {
+        &self.pixels
+    }
+
+    /// Colors a rectangle, leaving out any part of it outside the image
+    pub fn fill_rect(&mut self, x: i64, y: i64, width: i64, height: i64, color: [u8; 4]) {
+        let (left, right) = (x.max(0), (x + width).min(self.width));
+        let (top, bottom) = (y.max(0), (y + height).min(self.height));
+
+        for y in top..bottom {
+            for x in left..right {
+                self.pixels[(y * self.width + x) as usize] = color;
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ImageError {
+    Io(io::Error),
+    Png(png::DecodingError),
+    TooLarge,
+}
+
+impl Display for ImageError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Failed to read image: {}", e),
+            Self::Png(e) => write!(f, "Failed to decode image: {}", e),
+            Self::TooLarge => write!(f, "Image is too large"),
+        }
+    }
+}
+
+impl Error for ImageError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Png(e) => Some(e),
+
+            Self::TooLarge => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    use std::fs;
+    use std::process;
+
+    use super::*;
+
+    #[test]
+    fn written_images_are_read_back() {
+        let mut image = Image::new(7, 5, [10, 20, 30, 255]);
+        image.fill_rect(2, 1, 3, 10, [200, 100, 0, 128]);
+        image.fill_rect(-4, -4, 5, 5, [0, 0, 0, 0]);
+
+        let path = env::temp_dir().join(format!("maze_gen-image-{}.png", process::id()));
+        image.write_png(File::create(&path).unwrap()).unwrap();
+        let loaded = Image::load_png(&path);
+        fs::remove_file(&path).unwrap();
+
+        let loaded = loaded.unwrap();
+        assert_eq!((loaded.width(), loaded.height()), (7, 5));
+        assert_eq!(loaded.pixels(), image.pixels());
+        assert_eq!(loaded.pixel(0, 0), [0, 0, 0, 0]);
+        assert_eq!(loaded.pixel(3, 9), [200, 100, 0, 128]);
+    }
+
+    #[test]
+    fn missing_files_are_errors() {
+        assert!(matches!(
+            Image::load_png("no such directory/maze.png"),
+            Err(ImageError::Io(_))
+        ));
+    }
+}
@@ -0,0 +1,300 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::path::Path;
+
+use crate::image::{Image, ImageError};
+use crate::maze::{Algorithm, Maze, Topology};
+use crate::point::MazePoint;
+
+/// Where the cell edges of a maze are in an image, in pixels
+pub struct Grid {
+    /// The left edge of every column, and the right edge of the last one
+    pub x_positions: Vec<i64>,
+    /// The top edge of every row, and the bottom edge of the last one
+    pub y_positions: Vec<i64>,
+}
+
+impl Grid {
+    /// Evenly spaced cells filling the given area, as they're laid out for display
+    pub fn new(x: f64, y: f64, width: f64, height: f64, cols: i64, rows: i64) -> Self {
+        let positions = |start: f64, size: f64, count: i64| {
+            (0..=count)
+                .map(|i| (start + i as f64 / count as f64 * size) as i64)
+                .collect()
+        };
+
+        Self {
+            x_positions: positions(x, width, cols),
+            y_positions: positions(y, height, rows),
+        }
+    }
+
+    /// Cells of a known size, with the top left corner of the first one at `(x, y)`
+    pub fn with_cell_size(x: i64, y: i64, cell_size: i64, cols: i64, rows: i64) -> Self {
+        Self {
+            x_positions: (0..=cols).map(|col| x + col * cell_size).collect(),
+            y_positions: (0..=rows).map(|row| y + row * cell_size).collect(),
+        }
+    }
+
+    pub fn cols(&self) -> i64 {
+        self.x_positions.len() as i64 - 1
+    }
+
+    pub fn rows(&self) -> i64 {
+        self.y_positions.len() as i64 - 1
+    }
+}
+
+/// Loads an orthogonal maze from a PNG of its grid, such as an exported or scanned one
+///
+/// See [`from_image`].
+pub fn from_png(path: impl AsRef<Path>, grid: &Grid) -> Result<Maze, ImportError> {
+    from_image(&Image::load_png(path).map_err(ImportError::Image)?, grid)
+}
+
+/// Reconstructs an orthogonal maze from an image of its grid, by sampling the middle of every wall
+///
+/// The wall color is taken from the middle of the grid's outer edges, which are walls apart from
+/// a few entrances, so any colors can be used. Every wall between two cells is then kept
+/// if the middle of it is closer in color to the walls than to the middle of either cell,
+/// which still works when the cells are colored in, or when one of them has a marker in it.
+///
+/// Transparent pixels count as the white they'd be printed on. The whole grid must be
+/// part of the maze. The start and goal are the default ones.
+pub fn from_image(image: &Image, grid: &Grid) -> Result<Maze, ImportError> {
+    let cols = grid.cols();
+    let rows = grid.rows();
+
+    if cols < 1 || rows < 1 {
+        return Err(ImportError::EmptyGrid);
+    }
+
+    let is_inside_image = |positions: &[i64], size: i64| {
+        positions.windows(2).all(|edges| edges[0] < edges[1])
+            && positions[0] >= 0
+            && positions[positions.len() - 1] < size
+    };
+
+    if !is_inside_image(&grid.x_positions, image.width())
+        || !is_inside_image(&grid.y_positions, image.height())
+    {
+        return Err(ImportError::GridOutsideImage);
+    }
+
+    let x_middle =
+        |col: i64| (grid.x_positions[col as usize] + grid.x_positions[col as usize + 1]) / 2;
+    let y_middle =
+        |row: i64| (grid.y_positions[row as usize] + grid.y_positions[row as usize + 1]) / 2;
+    let x_edge = |col: i64| grid.x_positions[col as usize];
+    let y_edge = |row: i64| grid.y_positions[row as usize];
+
+    let outer_edges: Vec<[u8; 3]> = (0..cols)
+        .flat_map(|col| [(x_middle(col), y_edge(0)), (x_middle(col), y_edge(rows))])
+        .chain(
+            (0..rows).flat_map(|row| [(x_edge(0), y_middle(row)), (x_edge(cols), y_middle(row))]),
+        )
+        .map(|(x, y)| opaque_pixel(image, x, y))
+        .collect();
+    let wall_color = median(&outer_edges);
+
+    let center = |cell: MazePoint| opaque_pixel(image, x_middle(cell.x), y_middle(cell.y));
+    let is_wall = |sample: [u8; 3], cell1: MazePoint, cell2: MazePoint| {
+        let wall_distance = distance(sample, wall_color);
+
+        wall_distance < distance(sample, center(cell1))
+            && wall_distance < distance(sample, center(cell2))
+    };
+
+    let mut maze = Maze::new(
+        cols,
+        rows,
+        Topology::Orthogonal,
+        Algorithm::RecursiveBacktracker,
+    );
+
+    for y in 0..rows {
+        for x in 0..cols {
+            let cell = MazePoint::new(x, y);
+
+            if x < cols - 1 {
+                let right = cell + (1, 0);
+                let sample = opaque_pixel(image, x_edge(x + 1), y_middle(y));
+
+                maze.set_wall(cell, right, is_wall(sample, cell, right));
+            }
+
+            if y < rows - 1 {
+                let below = cell + (0, 1);
+                let sample = opaque_pixel(image, x_middle(x), y_edge(y + 1));
+
+                maze.set_wall(cell, below, is_wall(sample, cell, below));
+            }
+        }
+    }
+
+    maze.mark_finished();
+
+    Ok(maze)
+}
+
+/// The color of a pixel blended over white, clamped to the edges of the image
+fn opaque_pixel(image: &Image, x: i64, y: i64) -> [u8; 3] {
+    let [red, green, blue, alpha] = image.pixel(x, y);
+
+    [red, green, blue].map(|channel| {
+        let alpha = alpha as u32;
+        ((channel as u32 * alpha + u8::MAX as u32 * (255 - alpha)) / 255) as u8
+    })
+}
+
+/// The median of every channel separately
+fn median(colors: &[[u8; 3]]) -> [u8; 3] {
+    [0, 1, 2].map(|channel| {
+        let mut values: Vec<u8> = colors.iter().map(|color| color[channel]).collect();
+        values.sort_unstable();
+
+        values[values.len() / 2]
+    })
+}
+
+fn distance(color1: [u8; 3], color2: [u8; 3]) -> u32 {
+    color1
+        .iter()
+        .zip(color2)
+        .map(|(&a, b)| a.abs_diff(b) as u32)
+        .sum()
+}
+
+#[derive(Debug)]
+pub enum ImportError {
+    Image(ImageError),
+    EmptyGrid,
+    GridOutsideImage,
+}
+
+impl Display for ImportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Image(e) => write!(f, "{}", e),
+            Self::EmptyGrid => write!(f, "The grid must have at least one column and one row"),
+            Self::GridOutsideImage => write!(f, "The grid doesn't fit inside the image"),
+        }
+    }
+}
+
+impl Error for ImportError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Image(e) => Some(e),
+
+            Self::EmptyGrid | Self::GridOutsideImage => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, Snapshot};
+
+    const WHITE: [u8; 4] = [255, 255, 255, 255];
+    const BLACK: [u8; 4] = [0, 0, 0, 255];
+    const WALL_THICKNESS: i64 = 3;
+
+    /// Draws the walls of a maze as lines along the grid, over cells of a single color
+    fn draw(maze: &Maze, grid: &Grid, width: i64, height: i64, cells: [u8; 4]) -> Image {
+        let mut image = Image::new(width, height, WHITE);
+        let (x_edges, y_edges) = (&grid.x_positions, &grid.y_positions);
+        let half = WALL_THICKNESS / 2;
+
+        image.fill_rect(
+            x_edges[0],
+            y_edges[0],
+            x_edges[x_edges.len() - 1] - x_edges[0],
+            y_edges[y_edges.len() - 1] - y_edges[0],
+            cells,
+        );
+
+        for cell in maze.all_cells() {
+            let (x, y) = (cell.x as usize, cell.y as usize);
+            let (left, right) = (x_edges[x], x_edges[x + 1]);
+            let (top, bottom) = (y_edges[y], y_edges[y + 1]);
+
+            if !maze.is_passage(cell, cell + (-1, 0)) {
+                image.fill_rect(left - half, top - half, WALL_THICKNESS, bottom - top, BLACK);
+            }
+            if !maze.is_passage(cell, cell + (1, 0)) {
+                image.fill_rect(
+                    right - half,
+                    top - half,
+                    WALL_THICKNESS,
+                    bottom - top,
+                    BLACK,
+                );
+            }
+            if !maze.is_passage(cell, cell + (0, -1)) {
+                image.fill_rect(left - half, top - half, right - left, WALL_THICKNESS, BLACK);
+            }
+            if !maze.is_passage(cell, cell + (0, 1)) {
+                image.fill_rect(
+                    left - half,
+                    bottom - half,
+                    right - left,
+                    WALL_THICKNESS,
+                    BLACK,
+                );
+            }
+        }
+
+        image
+    }
+
+    #[test]
+    fn drawn_mazes_are_read_back() {
+        for seed in 0..4 {
+            let maze = testing::generated(11, 7, Topology::Orthogonal, seed);
+
+            let grid = Grid::with_cell_size(2, 2, 12, 11, 7);
+            let image = draw(&maze, &grid, 11 * 12 + 4, 7 * 12 + 4, WHITE);
+            let imported = from_image(&image, &grid).unwrap();
+
+            assert_eq!(Snapshot::of(&imported), Snapshot::of(&maze));
+        }
+    }
+
+    #[test]
+    fn grids_can_be_anywhere_in_the_image() {
+        let maze = testing::generated(6, 9, Topology::Orthogonal, 1);
+
+        // Uneven cells in the middle of a larger image, colored in, as they're laid out on screen
+        let grid = Grid::new(30.5, 12.0, 100.0, 170.0, 6, 9);
+        let image = draw(&maze, &grid, 200, 200, [60, 90, 160, 255]);
+        let imported = from_image(&image, &grid).unwrap();
+
+        assert_eq!(Snapshot::of(&imported), Snapshot::of(&maze));
+    }
+
+    #[test]
+    fn grids_must_fit_inside_the_image() {
+        let maze = testing::generated(5, 5, Topology::Orthogonal, 0);
+        let image = draw(&maze, &Grid::with_cell_size(0, 0, 10, 5, 5), 51, 51, WHITE);
+
+        for grid in [
+            Grid::with_cell_size(0, 0, 10, 6, 5),
+            Grid::with_cell_size(0, 0, 11, 5, 5),
+            Grid::with_cell_size(-1, 0, 10, 5, 5),
+            Grid::with_cell_size(1, 1, 10, 5, 5),
+        ] {
+            assert!(matches!(
+                from_image(&image, &grid),
+                Err(ImportError::GridOutsideImage)
+            ));
+        }
+
+        assert!(matches!(
+            from_image(&image, &Grid::with_cell_size(0, 0, 10, 0, 5)),
+            Err(ImportError::EmptyGrid)
+        ));
+    }
+}
@@ -0,0 +1,23 @@
+//! Step-by-step maze generation, independent of how the maze is displayed
+//!
+//! A [`Maze`] is created from its size or a [`Mask`], and advanced one step at a time
+//! with [`Maze::step`], so the generation process itself can be shown.
+
+pub mod args;
+pub mod image;
+pub mod import;
+pub mod mask;
+pub mod maze;
+pub mod point;
+pub mod render;
+pub mod save;
+pub mod share;
+pub mod solve;
+#[cfg(test)]
+mod testing;
+pub mod text;
+pub mod tiles;
+
+pub use mask::{Mask, MaskError};
+pub use maze::{Algorithm, Maze, Orientation, Topology, Wall};
+pub use point::MazePoint;
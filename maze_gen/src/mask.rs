@@ -0,0 +1,224 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use crate::image::{Image, ImageError};
+use crate::point::MazePoint;
+
+/// The set of cells of a Maze that are enabled
+///
+/// Disabled cells are not part of the maze at all: they have no walls,
+/// are never visited by the generator and aren't drawn.
+#[derive(Clone)]
+pub struct Mask {
+    width: i64,
+    height: i64,
+
+    enabled: Vec<bool>,
+}
+
+impl Mask {
+    /// A rectangular mask in which every cell is enabled
+    pub fn full(width: i64, height: i64) -> Self {
+        Self {
+            width,
+            height,
+            enabled: vec![true; (width * height) as usize],
+        }
+    }
+
+    /// Loads a mask from a file, as a PNG if its extension is `.png`, or as an ASCII template otherwise
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, MaskError> {
+        let path = path.as_ref();
+
+        let is_png = path
+            .extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("png"));
+
+        if is_png {
+            Self::from_png(path)
+        } else {
+            Self::from_ascii(&fs::read_to_string(path).map_err(MaskError::Io)?)
+        }
+    }
+
+    /// Parses an ASCII template, one line per row
+    ///
+    /// Spaces and `.` are disabled cells, any other character is an enabled cell.
+    /// Short lines are padded with disabled cells.
+    pub fn from_ascii(text: &str) -> Result<Self, MaskError> {
+        let mut lines: Vec<&str> = text.lines().collect();
+
+        while lines.last().is_some_and(|line| line.trim().is_empty()) {
+            lines.pop();
+        }
+
+        let width = lines
+            .iter()
+            .map(|line| line.chars().count())
+            .max()
+            .unwrap_or(0);
+        let height = lines.len();
+
+        let mut enabled = vec![false; width * height];
+
+        for (y, line) in lines.iter().enumerate() {
+            for (x, c) in line.chars().enumerate() {
+                enabled[y * width + x] = c != ' ' && c != '.';
+            }
+        }
+
+        Self::from_cells(width as i64, height as i64, enabled)
+    }
+
+    /// Decodes a black-and-white PNG, one pixel per cell
+    ///
+    /// Dark opaque pixels are enabled cells, light or transparent pixels are disabled cells.
+    pub fn from_png(path: impl AsRef<Path>) -> Result<Self, MaskError> {
+        let image = Image::load_png(path).map_err(MaskError::Image)?;
+
+        let enabled = image
+            .pixels()
+            .iter()
+            .map(|&[red, green, blue, alpha]| {
+                let luma = (red as u32 * 299 + green as u32 * 587 + blue as u32 * 114) / 1000;
+
+                luma < 128 && alpha >= 128
+            })
+            .collect();
+
+        Self::from_cells(image.width(), image.height(), enabled)
+    }
+
+    /// Resamples the mask to a different size, picking the nearest cell of the original
+    pub fn resized(&self, width: i64, height: i64) -> Result<Self, MaskError> {
+        let enabled = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let original_x = x * self.width / width;
+                let original_y = y * self.height / height;
+
+                self.is_enabled(MazePoint::new(original_x, original_y))
+            })
+            .collect();
+
+        Self::from_cells(width, height, enabled)
+    }
+
+    /// The mask, unless it's wider or taller than `max_size` cells
+    ///
+    /// A mask's file can give it any size, which is checked here against the largest maze
+    /// that can be made.
+    pub fn within_size(self, max_size: i64) -> Result<Self, MaskError> {
+        if self.width > max_size || self.height > max_size {
+            return Err(MaskError::TooLarge {
+                width: self.width,
+                height: self.height,
+                max_size,
+            });
+        }
+
+        Ok(self)
+    }
+
+    pub fn width(&self) -> i64 {
+        self.width
+    }
+
+    pub fn height(&self) -> i64 {
+        self.height
+    }
+
+    /// Whether the cell is inside the mask's bounds and enabled
+    pub fn is_enabled(&self, cell: MazePoint) -> bool {
+        cell.x >= 0
+            && cell.x < self.width
+            && cell.y >= 0
+            && cell.y < self.height
+            && self.enabled[(cell.y * self.width + cell.x) as usize]
+    }
+
+    pub(crate) fn from_cells(
+        width: i64,
+        height: i64,
+        enabled: Vec<bool>,
+    ) -> Result<Self, MaskError> {
+        if !enabled.contains(&true) {
+            return Err(MaskError::NoEnabledCells);
+        }
+
+        Ok(Self {
+            width,
+            height,
+            enabled,
+        })
+    }
+}
+
+#[derive(Debug)]
+pub enum MaskError {
+    Io(io::Error),
+    Image(ImageError),
+    NoEnabledCells,
+    TooLarge {
+        width: i64,
+        height: i64,
+        max_size: i64,
+    },
+}
+
+impl Display for MaskError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Failed to read mask: {}", e),
+            Self::Image(e) => write!(f, "Failed to load mask image: {}", e),
+            Self::NoEnabledCells => write!(f, "Mask has no enabled cells"),
+            Self::TooLarge {
+                width,
+                height,
+                max_size,
+            } => write!(
+                f,
+                "A {}x{} mask is too large, masks can be up to {} cells a side",
+                width, height, max_size
+            ),
+        }
+    }
+}
+
+impl Error for MaskError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Image(e) => Some(e),
+
+            Self::NoEnabledCells | Self::TooLarge { .. } => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn masks_larger_than_the_maximum_are_rejected() {
+        let mask = Mask::from_ascii("#####\n#...#\n#####\n").unwrap();
+
+        assert!(mask.clone().within_size(5).is_ok());
+        assert!(matches!(
+            mask.within_size(4),
+            Err(MaskError::TooLarge {
+                width: 5,
+                height: 3,
+                max_size: 4
+            })
+        ));
+        assert!(matches!(
+            Mask::full(2, 6).within_size(5),
+            Err(MaskError::TooLarge { .. })
+        ));
+    }
+}
@@ -0,0 +1,804 @@
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, HashSet};
+use std::mem;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use crate::mask::Mask;
+use crate::point::MazePoint;
+
+const DIRECTIONS: [(i64, i64); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+pub struct Maze {
+    mask: Mask,
+    topology: Topology,
+    algorithm: Algorithm,
+
+    walls: HashSet<Wall>,
+    under_passages: HashMap<MazePoint, Orientation>,
+    path: Vec<MazePoint>,
+    visited: HashSet<MazePoint>,
+
+    start: MazePoint,
+    goal: MazePoint,
+
+    /// The changes made by every generation step so far, which can be undone
+    history: Vec<Vec<Change>>,
+    /// Steps that were undone, most recent last, which stepping again redoes
+    undone: Vec<Vec<Change>>,
+    /// The changes made by the step being taken
+    changes: Vec<Change>,
+    /// Steps that were taken before the history was last forgotten
+    forgotten_steps: usize,
+    /// See [`Maze::revision`]
+    revision: u64,
+}
+
+impl Maze {
+    pub fn new(width: i64, height: i64, topology: Topology, algorithm: Algorithm) -> Self {
+        Self::with_mask(Mask::full(width, height), topology, algorithm)
+    }
+
+    pub fn with_mask(mask: Mask, topology: Topology, algorithm: Algorithm) -> Self {
+        let mut walls = HashSet::new();
+        Self::fill_all_walls(&mut walls, &mask);
+
+        let mut maze = Self {
+            mask,
+            topology,
+            algorithm,
+            walls,
+            under_passages: HashMap::new(),
+            path: vec![],
+            visited: HashSet::new(),
+            start: MazePoint::new(0, 0),
+            goal: MazePoint::new(0, 0),
+            history: Vec::new(),
+            undone: Vec::new(),
+            changes: Vec::new(),
+            forgotten_steps: 0,
+            revision: next_revision(),
+        };
+
+        // The start and goal are on opposite corners by default
+        let first_cell = maze.all_cells().next();
+        let last_cell = maze.all_cells().last();
+        maze.start = first_cell.unwrap_or(maze.start);
+        maze.goal = last_cell.unwrap_or(maze.goal);
+
+        maze.start_new_region();
+        maze.changes.clear();
+
+        maze
+    }
+
+    pub fn width(&self) -> i64 {
+        self.mask.width()
+    }
+
+    pub fn height(&self) -> i64 {
+        self.mask.height()
+    }
+
+    pub fn mask(&self) -> &Mask {
+        &self.mask
+    }
+
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    pub fn algorithm(&self) -> Algorithm {
+        self.algorithm
+    }
+
+    pub fn start(&self) -> MazePoint {
+        self.start
+    }
+
+    pub fn goal(&self) -> MazePoint {
+        self.goal
+    }
+
+    /// A number that changes whenever the maze does, and is never shared with another maze,
+    /// so that anything worked out from the maze can be kept until then
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// Moves the start, unless the cell isn't part of the maze
+    pub fn set_start(&mut self, cell: MazePoint) {
+        if self.is_point_inside(cell) {
+            self.start = cell;
+            self.revision = next_revision();
+        }
+    }
+
+    /// Moves the goal, unless the cell isn't part of the maze
+    pub fn set_goal(&mut self, cell: MazePoint) {
+        if self.is_point_inside(cell) {
+            self.goal = cell;
+            self.revision = next_revision();
+        }
+    }
+
+    /// Takes a generation step, or redoes the last step that was undone
+    ///
+    /// Redoing keeps the maze the same as it was before the undo, even though `rng` has moved on.
+    pub fn step(&mut self, rng: &mut impl Rng) {
+        if self.redo_step() {
+            return;
+        }
+
+        match self.algorithm {
+            Algorithm::RecursiveBacktracker => self.step_recursive_backtracker(rng),
+        }
+
+        let changes = mem::take(&mut self.changes);
+
+        if !changes.is_empty() {
+            self.history.push(changes);
+        }
+    }
+
+    /// Undoes the last generation step, returning whether there was one to undo
+    pub fn step_back(&mut self) -> bool {
+        let Some(changes) = self.history.pop() else {
+            return false;
+        };
+
+        for change in changes.iter().rev() {
+            self.revert(change);
+        }
+
+        self.undone.push(changes);
+
+        true
+    }
+
+    /// Undoes or redoes steps until `steps` of them have been taken,
+    /// as far as the steps taken and undone so far go
+    pub fn go_to_step(&mut self, steps: usize) {
+        while self.steps_taken() > steps && self.step_back() {}
+
+        while self.steps_taken() < steps && self.redo_step() {}
+    }
+
+    /// How many generation steps have changed the maze since it was created or last edited
+    pub fn steps_taken(&self) -> usize {
+        self.history.len()
+    }
+
+    /// How many generation steps have changed the maze since it was created,
+    /// including the ones that can no longer be undone because of an edit
+    pub fn total_steps(&self) -> usize {
+        self.forgotten_steps + self.history.len()
+    }
+
+    /// The steps taken and the steps undone after them, which is how far the maze can be redone
+    pub fn steps_recorded(&self) -> usize {
+        self.history.len() + self.undone.len()
+    }
+
+    /// Keeps stepping until the maze is finished
+    pub fn finish(&mut self, rng: &mut impl Rng) {
+        while !self.is_finished() {
+            self.step(rng);
+        }
+    }
+
+    /// Whether every cell has been visited, so stepping has no effect
+    pub fn is_finished(&self) -> bool {
+        self.path.is_empty()
+    }
+
+    /// All enabled cells, row by row
+    pub fn all_cells(&self) -> impl Iterator<Item = MazePoint> + '_ {
+        (0..self.height())
+            .flat_map(|y| (0..self.width()).map(move |x| MazePoint::new(x, y)))
+            .filter(|&cell| self.mask.is_enabled(cell))
+    }
+
+    pub fn cursor(&self) -> Option<MazePoint> {
+        self.path.last().copied()
+    }
+
+    pub fn is_visited(&self, cell: MazePoint) -> bool {
+        self.visited.contains(&cell)
+    }
+
+    pub fn visited_count(&self) -> usize {
+        self.visited.len()
+    }
+
+    /// How many cells are part of the maze, which is fewer than its area when it has a mask
+    pub fn cell_count(&self) -> usize {
+        self.all_cells().count()
+    }
+
+    /// Whether the cell is part of the maze, i.e. inside its bounds and not masked out
+    pub fn is_point_inside(&self, point: MazePoint) -> bool {
+        self.mask.is_enabled(point)
+    }
+
+    /// Whether the two cells are adjacent, and there's no wall between them
+    ///
+    /// In a weave maze this is also true for the two sides of an under-passage.
+    pub fn is_passage(&self, cell1: MazePoint, cell2: MazePoint) -> bool {
+        self.is_point_inside(cell1)
+            && self.is_point_inside(cell2)
+            && Wall::new(cell1, cell2).is_ok_and(|wall| !self.walls.contains(&wall))
+    }
+
+    /// Whether the cell is open on all four sides, which it must be to have a passage beneath it
+    pub(crate) fn is_crossing(&self, cell: MazePoint) -> bool {
+        DIRECTIONS
+            .into_iter()
+            .all(|dir| self.is_passage(cell, cell + dir))
+    }
+
+    /// The orientation of the passage tunnelling beneath the cell, if there is one
+    pub fn under_passage(&self, cell: MazePoint) -> Option<Orientation> {
+        self.under_passages.get(&cell).copied()
+    }
+
+    pub fn walls(&self) -> impl Iterator<Item = &Wall> {
+        self.walls.iter()
+    }
+
+    /// Adds or removes the wall between two adjacent cells of the maze
+    ///
+    /// Generation steps can't be undone past a change made by hand, so they're forgotten.
+    pub fn set_wall(&mut self, cell1: MazePoint, cell2: MazePoint, is_wall: bool) {
+        if !self.is_point_inside(cell1) || !self.is_point_inside(cell2) {
+            return;
+        }
+
+        self.forget_history();
+        self.revision = next_revision();
+
+        if let Ok(wall) = Wall::new(cell1, cell2) {
+            if is_wall {
+                self.walls.insert(wall);
+            } else {
+                self.walls.remove(&wall);
+            }
+        }
+    }
+
+    /// Records a passage tunnelling beneath the cell, for mazes that are read or edited
+    /// rather than generated
+    pub fn set_under_passage(&mut self, cell: MazePoint, orientation: Orientation) {
+        self.forget_history();
+        self.revision = next_revision();
+        self.under_passages.insert(cell, orientation);
+    }
+
+    /// Fills in the passage tunnelling beneath the cell, returning it if there was one
+    ///
+    /// Like [`Maze::set_wall`], this forgets the generation steps taken so far.
+    pub fn remove_under_passage(&mut self, cell: MazePoint) -> Option<Orientation> {
+        let orientation = self.under_passages.remove(&cell)?;
+
+        self.forget_history();
+        self.revision = next_revision();
+
+        Some(orientation)
+    }
+
+    pub(crate) fn under_passages(&self) -> impl Iterator<Item = (MazePoint, Orientation)> + '_ {
+        self.under_passages
+            .iter()
+            .map(|(&cell, &orientation)| (cell, orientation))
+    }
+
+    pub(crate) fn visited_cells(&self) -> impl Iterator<Item = MazePoint> + '_ {
+        self.visited.iter().copied()
+    }
+
+    /// The generator's active cells, the last of which is the cursor
+    pub(crate) fn path(&self) -> &[MazePoint] {
+        &self.path
+    }
+
+    /// Replaces the generator's progress, for mazes that are loaded rather than generated
+    pub(crate) fn set_progress(&mut self, visited: HashSet<MazePoint>, path: Vec<MazePoint>) {
+        self.forget_history();
+        self.revision = next_revision();
+        self.visited = visited;
+        self.path = path;
+    }
+
+    /// Marks every cell as visited and ends generation, for mazes that are read rather than generated
+    pub(crate) fn mark_finished(&mut self) {
+        self.forget_history();
+        self.revision = next_revision();
+        self.visited = self.all_cells().collect();
+        self.path.clear();
+    }
+
+    fn step_recursive_backtracker(&mut self, rng: &mut impl Rng) {
+        if let Some(cursor) = self.cursor() {
+            if let Some(&carve) = self.possible_carves(cursor).choose(rng) {
+                self.carve(cursor, carve);
+
+                self.record(Change::PathPushed(carve.to));
+            } else {
+                self.remove_from_path(self.path.len() - 1);
+
+                if self.path.is_empty() {
+                    self.start_new_region();
+                }
+            }
+        }
+    }
+
+    /// Every passage that can be carved from the cell into an unvisited cell
+    fn possible_carves(&self, cell: MazePoint) -> Vec<Carve> {
+        DIRECTIONS
+            .into_iter()
+            .filter_map(|dir| {
+                let step = cell + dir;
+
+                if self.is_point_inside(step) && !self.is_visited(step) {
+                    Some(Carve {
+                        to: step,
+                        under: None,
+                    })
+                } else if self.can_tunnel_under(cell, dir) {
+                    Some(Carve {
+                        to: step + dir,
+                        under: Some(step),
+                    })
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
+    fn carve(&mut self, from: MazePoint, carve: Carve) {
+        match carve.under {
+            Some(under_cell) => {
+                self.record(Change::WallRemoved(Wall::new(from, under_cell).unwrap()));
+                self.record(Change::WallRemoved(
+                    Wall::new(under_cell, carve.to).unwrap(),
+                ));
+
+                let orientation = if from.y == carve.to.y {
+                    Orientation::Horizontal
+                } else {
+                    Orientation::Vertical
+                };
+                self.record(Change::UnderPassageAdded(under_cell, orientation));
+            }
+
+            None => {
+                self.record(Change::WallRemoved(Wall::new(from, carve.to).unwrap()));
+            }
+        }
+
+        self.record(Change::Visited(carve.to));
+    }
+
+    /// Whether a passage from `cell` can go under its neighbour in `dir`
+    /// to reach the unvisited cell right behind it
+    ///
+    /// That's only possible if the neighbour is a straight corridor perpendicular to `dir`,
+    /// which doesn't already have a passage under it.
+    fn can_tunnel_under(&self, cell: MazePoint, dir: (i64, i64)) -> bool {
+        let under_cell = cell + dir;
+        let step = under_cell + dir;
+        let sides = (dir.1, dir.0);
+
+        self.topology == Topology::Weave
+            && self.is_point_inside(step)
+            && !self.is_visited(step)
+            && self.under_passage(under_cell).is_none()
+            && !self.is_passage(under_cell, cell)
+            && !self.is_passage(under_cell, step)
+            && self.is_passage(under_cell, under_cell + sides)
+            && self.is_passage(under_cell, under_cell - sides)
+    }
+
+    /// Continues generation from an unvisited cell once the path has backtracked all the way,
+    /// which only happens when the mask splits the maze into several disconnected regions
+    fn start_new_region(&mut self) {
+        let unvisited_cell = self.all_cells().find(|&cell| !self.is_visited(cell));
+
+        if let Some(cell) = unvisited_cell {
+            self.record(Change::PathPushed(cell));
+            self.record(Change::Visited(cell));
+        }
+    }
+
+    /// Redoes the last step that was undone, returning whether there was one
+    fn redo_step(&mut self) -> bool {
+        let Some(changes) = self.undone.pop() else {
+            return false;
+        };
+
+        for change in &changes {
+            self.apply(change);
+        }
+
+        self.history.push(changes);
+
+        true
+    }
+
+    fn remove_from_path(&mut self, index: usize) {
+        self.record(Change::PathRemoved(index, self.path[index]));
+    }
+
+    /// Makes a change for the step being taken, and remembers it if it did anything
+    fn record(&mut self, change: Change) {
+        if self.apply(&change) {
+            self.changes.push(change);
+        }
+    }
+
+    /// Returns whether the change did anything, since the sets may already be that way
+    fn apply(&mut self, change: &Change) -> bool {
+        self.revision = next_revision();
+
+        match *change {
+            Change::WallRemoved(wall) => self.walls.remove(&wall),
+            Change::UnderPassageAdded(cell, orientation) => match self.under_passages.entry(cell) {
+                Entry::Vacant(entry) => {
+                    entry.insert(orientation);
+                    true
+                }
+                Entry::Occupied(_) => false,
+            },
+            Change::Visited(cell) => self.visited.insert(cell),
+            Change::PathPushed(cell) => {
+                self.path.push(cell);
+                true
+            }
+            Change::PathRemoved(index, _) => {
+                self.path.remove(index);
+                true
+            }
+        }
+    }
+
+    fn revert(&mut self, change: &Change) {
+        self.revision = next_revision();
+
+        match *change {
+            Change::WallRemoved(wall) => {
+                self.walls.insert(wall);
+            }
+            Change::UnderPassageAdded(cell, _) => {
+                self.under_passages.remove(&cell);
+            }
+            Change::Visited(cell) => {
+                self.visited.remove(&cell);
+            }
+            Change::PathPushed(_) => {
+                self.path.pop();
+            }
+            Change::PathRemoved(index, cell) => self.path.insert(index, cell),
+        }
+    }
+
+    fn forget_history(&mut self) {
+        self.forgotten_steps += self.history.len();
+        self.history.clear();
+        self.undone.clear();
+    }
+
+    fn fill_all_walls(walls: &mut HashSet<Wall>, mask: &Mask) {
+        for y in 0..mask.height() {
+            for x in 0..mask.width() {
+                let current = MazePoint::new(x, y);
+                let right = current + (1, 0);
+                let down = current + (0, 1);
+
+                if !mask.is_enabled(current) {
+                    continue;
+                }
+
+                if mask.is_enabled(right) {
+                    walls.insert(Wall::new(current, right).unwrap());
+                }
+
+                if mask.is_enabled(down) {
+                    walls.insert(Wall::new(current, down).unwrap());
+                }
+            }
+        }
+    }
+}
+
+/// A single change that a generation step makes to a maze, which can be reverted
+enum Change {
+    WallRemoved(Wall),
+    UnderPassageAdded(MazePoint, Orientation),
+    Visited(MazePoint),
+    PathPushed(MazePoint),
+    /// A cell removed from the path, and where in the path it was
+    PathRemoved(usize, MazePoint),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Topology {
+    /// Every passage connects two adjacent cells
+    Orthogonal,
+
+    /// A passage may also tunnel under a perpendicular corridor to reach the cell behind it
+    Weave,
+}
+
+impl Topology {
+    pub fn all() -> &'static [Topology] {
+        &[Topology::Orthogonal, Topology::Weave]
+    }
+
+    /// A short name, for use in command-line arguments and files
+    pub fn name(&self) -> &'static str {
+        match self {
+            Topology::Orthogonal => "orthogonal",
+            Topology::Weave => "weave",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all()
+            .iter()
+            .copied()
+            .find(|topology| topology.name() == name)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    RecursiveBacktracker,
+}
+
+impl Algorithm {
+    pub fn all() -> &'static [Algorithm] {
+        &[Algorithm::RecursiveBacktracker]
+    }
+
+    /// A short name, for use in command-line arguments and files
+    pub fn name(&self) -> &'static str {
+        match self {
+            Algorithm::RecursiveBacktracker => "backtracker",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all()
+            .iter()
+            .copied()
+            .find(|algorithm| algorithm.name() == name)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Orientation {
+    Horizontal,
+    Vertical,
+}
+
+impl Orientation {
+    pub fn all() -> &'static [Orientation] {
+        &[Orientation::Horizontal, Orientation::Vertical]
+    }
+
+    /// A short name, for use in files
+    pub fn name(&self) -> &'static str {
+        match self {
+            Orientation::Horizontal => "horizontal",
+            Orientation::Vertical => "vertical",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all()
+            .iter()
+            .copied()
+            .find(|orientation| orientation.name() == name)
+    }
+}
+
+/// A passage the generator can carve from a cell
+#[derive(Clone, Copy)]
+struct Carve {
+    to: MazePoint,
+    /// The cell the passage tunnels under, in a weave maze
+    under: Option<MazePoint>,
+}
+
+/// A wall between two cells in a Maze
+///
+/// Enforces an invariat to make sure the two cells are adjacent,
+/// and that the internal order of the cells doesn't depend on the provided order,
+/// so that equality checks and hashes don't depend the order.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Wall(MazePoint, MazePoint);
+
+impl Wall {
+    fn new(cell1: MazePoint, cell2: MazePoint) -> Result<Self, String> {
+        let x_diff = (cell2.x - cell1.x).abs();
+        let y_diff = (cell2.y - cell1.y).abs();
+
+        if x_diff + y_diff != 1 {
+            return Err(
+                "A wall must be between two adjacent cells (no diagonals allowed)".to_string(),
+            );
+        }
+
+        use std::cmp::Ordering::*;
+
+        match (cell1.x.cmp(&cell2.x), cell1.y.cmp(&cell2.y)) {
+            (_, Less) | (Less, Equal) => Ok(Self(cell1, cell2)),
+
+            (_, Greater) | (Greater, Equal) => Ok(Self(cell2, cell1)),
+
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn first_cell(&self) -> MazePoint {
+        self.0
+    }
+
+    pub fn second_cell(&self) -> MazePoint {
+        self.1
+    }
+}
+
+/// A revision no maze has had yet
+fn next_revision() -> u64 {
+    static NEXT_REVISION: AtomicU64 = AtomicU64::new(0);
+
+    NEXT_REVISION.fetch_add(1, Ordering::Relaxed)
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    use super::*;
+    use crate::solve;
+    use crate::testing::Snapshot;
+
+    /// How many pairs of adjacent cells have no wall between them
+    fn passage_count(maze: &Maze) -> usize {
+        maze.all_cells()
+            .flat_map(|cell| [(cell, cell + (1, 0)), (cell, cell + (0, 1))])
+            .filter(|&(cell1, cell2)| maze.is_passage(cell1, cell2))
+            .count()
+    }
+
+    #[test]
+    fn every_algorithm_generates_a_perfect_maze() {
+        for &algorithm in Algorithm::all() {
+            for &topology in Topology::all() {
+                for seed in 0..4 {
+                    let mut maze = Maze::new(12, 9, topology, algorithm);
+                    maze.finish(&mut ChaCha8Rng::seed_from_u64(seed));
+
+                    let name = format!("{} {} {}", algorithm.name(), topology.name(), seed);
+
+                    assert_eq!(maze.visited_count(), maze.cell_count(), "{}", name);
+                    assert_eq!(
+                        solve::distances(&maze, maze.start()).len(),
+                        maze.cell_count(),
+                        "{}",
+                        name
+                    );
+
+                    // An under-passage takes down the two walls of the cell above it as well,
+                    // without connecting anything the passage on top doesn't already connect
+                    let tree_passages = maze.cell_count() - 1 + maze.under_passages().count();
+                    assert_eq!(passage_count(&maze), tree_passages, "{}", name);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn every_algorithm_keeps_masked_regions_connected() {
+        let mask = Mask::from_ascii("###.###\n###.###\n###.###\n").unwrap();
+
+        for &algorithm in Algorithm::all() {
+            let mut maze = Maze::with_mask(mask.clone(), Topology::Orthogonal, algorithm);
+            maze.finish(&mut ChaCha8Rng::seed_from_u64(7));
+
+            assert_eq!(maze.visited_count(), 18, "{}", algorithm.name());
+            assert_eq!(passage_count(&maze), 16, "{}", algorithm.name());
+        }
+    }
+
+    #[test]
+    fn revisions_change_with_the_maze() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut maze = Maze::new(5, 5, Topology::Orthogonal, Algorithm::RecursiveBacktracker);
+        let other = Maze::new(5, 5, Topology::Orthogonal, Algorithm::RecursiveBacktracker);
+        let mut revisions = vec![other.revision(), maze.revision()];
+
+        maze.step(&mut rng);
+        revisions.push(maze.revision());
+        maze.step_back();
+        revisions.push(maze.revision());
+        maze.set_wall(MazePoint::new(0, 0), MazePoint::new(1, 0), false);
+        revisions.push(maze.revision());
+        maze.set_goal(MazePoint::new(2, 2));
+        revisions.push(maze.revision());
+
+        revisions.sort_unstable();
+        revisions.dedup();
+        assert_eq!(revisions.len(), 6);
+
+        // Nothing changes when looking at the maze
+        let revision = maze.revision();
+        solve::solution(&maze);
+        assert_eq!(maze.revision(), revision);
+    }
+
+    #[test]
+    fn stepping_back_and_forth_restores_the_maze() {
+        for &algorithm in Algorithm::all() {
+            for &topology in Topology::all() {
+                let mut rng = ChaCha8Rng::seed_from_u64(7);
+                let mut maze = Maze::new(8, 6, topology, algorithm);
+                let mut snapshots = vec![Snapshot::of(&maze)];
+
+                for _ in 0..30 {
+                    maze.step(&mut rng);
+                    snapshots.push(Snapshot::of(&maze));
+                }
+
+                let name = format!("{} {}", algorithm.name(), topology.name());
+                assert_eq!(maze.steps_taken(), 30, "{}", name);
+
+                for steps_back in [1, 10, 30] {
+                    for _ in 0..steps_back {
+                        assert!(maze.step_back(), "{}", name);
+                    }
+                    assert_eq!(Snapshot::of(&maze), snapshots[30 - steps_back], "{}", name);
+
+                    // Redoing ignores the generator, so a different one gives the same steps
+                    let mut other_rng = ChaCha8Rng::seed_from_u64(steps_back as u64);
+                    for _ in 0..steps_back {
+                        maze.step(&mut other_rng);
+                    }
+                    assert_eq!(Snapshot::of(&maze), snapshots[30], "{}", name);
+                }
+
+                for steps in [0, 12, 30, 5] {
+                    maze.go_to_step(steps);
+                    assert_eq!(maze.steps_taken(), steps, "{}", name);
+                    assert_eq!(Snapshot::of(&maze), snapshots[steps], "{}", name);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn edits_keep_count_of_the_steps_taken() {
+        let mut rng = ChaCha8Rng::seed_from_u64(0);
+        let mut maze = Maze::new(6, 6, Topology::Orthogonal, Algorithm::RecursiveBacktracker);
+
+        for _ in 0..10 {
+            maze.step(&mut rng);
+        }
+        maze.step_back();
+        maze.set_wall(MazePoint::new(0, 0), MazePoint::new(0, 1), true);
+
+        assert_eq!(maze.steps_taken(), 0);
+        assert_eq!(maze.total_steps(), 9);
+
+        maze.step(&mut rng);
+
+        assert_eq!(maze.steps_taken(), 1);
+        assert_eq!(maze.total_steps(), 10);
+    }
+}
@@ -0,0 +1,93 @@
+use std::ops::{Add, Div, Mul, Sub};
+
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct MazePoint {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl MazePoint {
+    pub fn new(x: i64, y: i64) -> Self {
+        Self { x, y }
+    }
+}
+
+impl From<(i64, i64)> for MazePoint {
+    fn from(value: (i64, i64)) -> Self {
+        Self::new(value.0, value.1)
+    }
+}
+
+impl From<MazePoint> for (i64, i64) {
+    fn from(value: MazePoint) -> Self {
+        (value.x, value.y)
+    }
+}
+
+impl<T> Add<T> for MazePoint
+where
+    T: Into<MazePoint>,
+{
+    type Output = MazePoint;
+
+    fn add(self, rhs: T) -> Self::Output {
+        let rhs = rhs.into();
+
+        Self::Output::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl<T> Sub<T> for MazePoint
+where
+    T: Into<MazePoint>,
+{
+    type Output = MazePoint;
+
+    fn sub(self, rhs: T) -> Self::Output {
+        let rhs = rhs.into();
+
+        Self::Output::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl<T> Mul<T> for MazePoint
+where
+    T: Into<MazePoint>,
+{
+    type Output = MazePoint;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        let rhs = rhs.into();
+
+        Self::Output::new(self.x * rhs.x, self.y * rhs.y)
+    }
+}
+
+impl Mul<i64> for MazePoint {
+    type Output = MazePoint;
+
+    fn mul(self, rhs: i64) -> Self::Output {
+        Self::Output::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl<T> Div<T> for MazePoint
+where
+    T: Into<MazePoint>,
+{
+    type Output = MazePoint;
+
+    fn div(self, rhs: T) -> Self::Output {
+        let rhs = rhs.into();
+
+        Self::Output::new(self.x / rhs.x, self.y / rhs.y)
+    }
+}
+
+impl Div<i64> for MazePoint {
+    type Output = MazePoint;
+
+    fn div(self, rhs: i64) -> Self::Output {
+        Self::Output::new(self.x / rhs, self.y / rhs)
+    }
+}
@@ -0,0 +1,230 @@
+//! Draws mazes as pixels, for writing images without a window
+//!
+//! The app draws through SDL, with antialiased markers and its own layout, so these images
+//! aren't pixel-identical to the window's. They're laid out the same way though,
+//! with the same default colors.
+
+use crate::image::Image;
+use crate::maze::{Maze, Orientation, Topology};
+use crate::point::MazePoint;
+
+/// How a maze is drawn, in pixels and RGBA colors
+#[derive(Clone, Copy)]
+pub struct RenderOptions {
+    pub cell_size: i64,
+    pub wall_thickness: i64,
+    /// How far the corridors of a weave maze are from the edges of their cells,
+    /// as a fraction of a cell
+    pub weave_inset: f64,
+    pub background: [u8; 4],
+    pub wall: [u8; 4],
+    pub start: [u8; 4],
+    pub goal: [u8; 4],
+}
+
+impl Default for RenderOptions {
+    /// The colors of the app's default theme
+    fn default() -> Self {
+        Self {
+            cell_size: 16,
+            wall_thickness: 2,
+            weave_inset: 0.2,
+            background: [0x28, 0x24, 0x2E, 0xFF],
+            wall: [0xFF, 0xFB, 0xDE, 0xFF],
+            start: [0x81, 0xC0, 0xC6, 0xFF],
+            goal: [0xE8, 0xB0, 0x4B, 0xFF],
+        }
+    }
+}
+
+/// Draws the cells, walls, start and goal of a maze, leaving anything outside its mask transparent
+///
+/// The image is `cell_size` pixels per cell, plus half a wall around the outside, so that
+/// the outer walls are as thick as the others. The top left corner of the first cell
+/// is at half a wall from the image's.
+pub fn render(maze: &Maze, options: RenderOptions) -> Image {
+    let RenderOptions {
+        cell_size,
+        wall_thickness,
+        ..
+    } = options;
+    let margin = wall_thickness / 2;
+
+    let mut image = Image::new(
+        maze.width() * cell_size + wall_thickness,
+        maze.height() * cell_size + wall_thickness,
+        [0; 4],
+    );
+
+    let corner = |cell: MazePoint| (margin + cell.x * cell_size, margin + cell.y * cell_size);
+
+    for cell in maze.all_cells() {
+        let (x, y) = corner(cell);
+
+        image.fill_rect(x, y, cell_size, cell_size, options.background);
+    }
+
+    let lines = match maze.topology() {
+        Topology::Orthogonal => orthogonal_wall_lines(maze, options),
+        Topology::Weave => weave_wall_lines(maze, options),
+    };
+
+    for ((x1, y1), (x2, y2)) in lines {
+        image.fill_rect(
+            x1.min(x2) - margin,
+            y1.min(y2) - margin,
+            x1.abs_diff(x2) as i64 + wall_thickness,
+            y1.abs_diff(y2) as i64 + wall_thickness,
+            options.wall,
+        );
+    }
+
+    for (cell, color) in [(maze.start(), options.start), (maze.goal(), options.goal)] {
+        let (x, y) = corner(cell);
+        let center = cell_size as f64 / 2.0;
+        let radius = cell_size as f64 / 4.0;
+
+        for dy in 0..cell_size {
+            for dx in 0..cell_size {
+                let distance = (dx as f64 + 0.5 - center).hypot(dy as f64 + 0.5 - center);
+
+                if distance <= radius {
+                    image.fill_rect(x + dx, y + dy, 1, 1, color);
+                }
+            }
+        }
+    }
+
+    image
+}
+
+type Line = ((i64, i64), (i64, i64));
+
+/// Every edge of a cell with a wall or the outside of the maze on the other side
+fn orthogonal_wall_lines(maze: &Maze, options: RenderOptions) -> Vec<Line> {
+    let size = options.cell_size;
+    let margin = options.wall_thickness / 2;
+
+    maze.all_cells()
+        .flat_map(|cell| {
+            let (x0, y0) = (margin + cell.x * size, margin + cell.y * size);
+            let (x1, y1) = (x0 + size, y0 + size);
+
+            [
+                ((0, -1), ((x0, y0), (x1, y0))),
+                ((0, 1), ((x0, y1), (x1, y1))),
+                ((-1, 0), ((x0, y0), (x0, y1))),
+                ((1, 0), ((x1, y0), (x1, y1))),
+            ]
+            .into_iter()
+            .filter(move |&(dir, _)| !maze.is_passage(cell, cell + dir))
+            .map(|(_, line)| line)
+        })
+        .collect()
+}
+
+/// The walls of every corridor, inset from the cell edges, with a passage tunnelling beneath
+/// a cell showing as gaps in the side walls of the one on top
+fn weave_wall_lines(maze: &Maze, options: RenderOptions) -> Vec<Line> {
+    let size = options.cell_size;
+    let margin = options.wall_thickness / 2;
+    let inset = (size as f64 * options.weave_inset) as i64;
+
+    let mut lines = vec![];
+
+    for cell in maze.all_cells() {
+        let x0 = margin + cell.x * size;
+        let y0 = margin + cell.y * size;
+        let [x1, x2, x3] = [x0 + inset, x0 + size - inset, x0 + size];
+        let [y1, y2, y3] = [y0 + inset, y0 + size - inset, y0 + size];
+
+        let under_passage = maze.under_passage(cell);
+
+        for (dir, orientation, corridor_sides, inset_edge) in [
+            (
+                (0, -1),
+                Orientation::Vertical,
+                [((x1, y0), (x1, y1)), ((x2, y0), (x2, y1))],
+                ((x1, y1), (x2, y1)),
+            ),
+            (
+                (0, 1),
+                Orientation::Vertical,
+                [((x1, y2), (x1, y3)), ((x2, y2), (x2, y3))],
+                ((x1, y2), (x2, y2)),
+            ),
+            (
+                (-1, 0),
+                Orientation::Horizontal,
+                [((x0, y1), (x1, y1)), ((x0, y2), (x1, y2))],
+                ((x1, y1), (x1, y2)),
+            ),
+            (
+                (1, 0),
+                Orientation::Horizontal,
+                [((x2, y1), (x3, y1)), ((x2, y2), (x3, y2))],
+                ((x2, y1), (x2, y2)),
+            ),
+        ] {
+            let is_passage = maze.is_passage(cell, cell + dir);
+
+            if is_passage {
+                lines.extend(corridor_sides);
+            }
+
+            if !is_passage || under_passage == Some(orientation) {
+                lines.push(inset_edge);
+            }
+        }
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::import::{self, Grid};
+    use crate::testing::{self, Snapshot};
+
+    #[test]
+    fn rendered_mazes_can_be_imported() {
+        let options = RenderOptions {
+            cell_size: 12,
+            wall_thickness: 3,
+            ..RenderOptions::default()
+        };
+
+        for seed in 0..4 {
+            let maze = testing::generated(9, 6, Topology::Orthogonal, seed);
+            let image = render(&maze, options);
+
+            assert_eq!((image.width(), image.height()), (9 * 12 + 3, 6 * 12 + 3));
+
+            let grid = Grid::with_cell_size(1, 1, 12, 9, 6);
+            let imported = import::from_image(&image, &grid).unwrap();
+
+            assert_eq!(Snapshot::of(&imported), Snapshot::of(&maze));
+        }
+    }
+
+    #[test]
+    fn cells_outside_the_mask_are_transparent() {
+        let options = RenderOptions::default();
+        let maze = testing::masked(Topology::Weave, 0);
+        let image = render(&maze, options);
+
+        let center = |cell: MazePoint| {
+            let offset = options.wall_thickness / 2 + options.cell_size / 2;
+            image.pixel(
+                offset + cell.x * options.cell_size,
+                offset + cell.y * options.cell_size,
+            )
+        };
+
+        assert_eq!(center(MazePoint::new(0, 0)), [0; 4]);
+        assert_eq!(center(MazePoint::new(4, 2)), [0; 4]);
+        assert_eq!(center(maze.start()), options.start);
+        assert_eq!(center(maze.goal()), options.goal);
+    }
+}
@@ -0,0 +1,532 @@
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt::{self, Display};
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::mask::Mask;
+use crate::maze::{Algorithm, Maze, Orientation, Topology};
+use crate::point::MazePoint;
+
+/// Changes whenever the format does, so that older files are rejected instead of misread
+pub const FORMAT_VERSION: u64 = 1;
+
+/// A maze with everything needed to continue generating it exactly where it stopped
+pub struct SavedMaze {
+    pub maze: Maze,
+    /// The seed the maze was first generated with
+    pub seed: u64,
+    /// The random number generator, in the state it was in when the maze was saved
+    pub rng: ChaCha8Rng,
+}
+
+/// Saves the maze, the seed it was generated with and the current state of the generator's RNG
+pub fn to_json(maze: &Maze, seed: u64, rng: &ChaCha8Rng) -> String {
+    let mask = (0..maze.height())
+        .map(|y| {
+            (0..maze.width())
+                .map(|x| {
+                    if maze.is_point_inside(MazePoint::new(x, y)) {
+                        '#'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut walls: Vec<_> = maze
+        .walls()
+        .map(|wall| (point(wall.first_cell()), point(wall.second_cell())))
+        .collect();
+    let mut under_passages: Vec<_> = maze
+        .under_passages()
+        .map(|(cell, orientation)| UnderPassageFile {
+            cell: point(cell),
+            orientation: orientation.name().to_string(),
+        })
+        .collect();
+    let mut visited: Vec<_> = maze.visited_cells().map(point).collect();
+
+    // Sets have no order, but the same maze should always be saved the same way
+    walls.sort();
+    under_passages.sort_by_key(|under_passage| under_passage.cell);
+    visited.sort();
+
+    let file = MazeFile {
+        version: FORMAT_VERSION,
+        width: maze.width(),
+        height: maze.height(),
+        mask,
+        topology: maze.topology().name().to_string(),
+        algorithm: maze.algorithm().name().to_string(),
+        seed,
+        rng: RngFile {
+            seed: rng
+                .get_seed()
+                .iter()
+                .map(|byte| format!("{:02x}", byte))
+                .collect(),
+            stream: rng.get_stream(),
+            word_pos: rng.get_word_pos(),
+        },
+        start: point(maze.start()),
+        goal: point(maze.goal()),
+        walls,
+        under_passages,
+        visited,
+        path: maze.path().iter().copied().map(point).collect(),
+    };
+
+    // Serializing plain data to a string can't fail
+    serde_json::to_string(&file).unwrap() + "\n"
+}
+
+/// Loads a maze saved by [`to_json`], checking that it's consistent
+pub fn from_json(json: &str) -> Result<SavedMaze, LoadError> {
+    let value: Value = serde_json::from_str(json).map_err(LoadError::Json)?;
+
+    let version = value
+        .get("version")
+        .and_then(Value::as_u64)
+        .ok_or(LoadError::MissingVersion)?;
+
+    if version != FORMAT_VERSION {
+        return Err(LoadError::UnsupportedVersion(version));
+    }
+
+    let file: MazeFile = serde_json::from_value(value).map_err(LoadError::Json)?;
+
+    let mask = parse_mask(&file)?;
+    let topology = Topology::from_name(&file.topology)
+        .ok_or_else(|| LoadError::invalid_name("topology", &file.topology))?;
+    let algorithm = Algorithm::from_name(&file.algorithm)
+        .ok_or_else(|| LoadError::invalid_name("algorithm", &file.algorithm))?;
+    let rng = parse_rng(&file.rng)?;
+
+    let mut maze = Maze::with_mask(mask, topology, algorithm);
+
+    let inside = |field, cell: (i64, i64)| {
+        let cell = MazePoint::new(cell.0, cell.1);
+
+        if maze.is_point_inside(cell) {
+            Ok(cell)
+        } else {
+            Err(LoadError::CellOutsideMaze { field, cell })
+        }
+    };
+
+    let start = inside("start", file.start)?;
+    let goal = inside("goal", file.goal)?;
+
+    let mut walls = vec![];
+    for &(cell1, cell2) in &file.walls {
+        let cell1 = inside("walls", cell1)?;
+        let cell2 = inside("walls", cell2)?;
+
+        if (cell1.x - cell2.x).abs() + (cell1.y - cell2.y).abs() != 1 {
+            return Err(LoadError::WallNotBetweenNeighbours(cell1, cell2));
+        }
+
+        walls.push((cell1, cell2));
+    }
+
+    let mut under_passages = vec![];
+    for under_passage in &file.under_passages {
+        let UnderPassageFile { cell, orientation } = under_passage;
+
+        let cell = inside("under_passages", *cell)?;
+        let orientation = Orientation::from_name(orientation)
+            .ok_or_else(|| LoadError::invalid_name("under_passages", orientation))?;
+
+        under_passages.push((cell, orientation));
+    }
+
+    let visited = file
+        .visited
+        .iter()
+        .map(|&cell| inside("visited", cell))
+        .collect::<Result<HashSet<_>, _>>()?;
+    let path = file
+        .path
+        .iter()
+        .map(|&cell| inside("path", cell))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    // The generator relies on every cell of its path being visited,
+    // and on every cell being visited once the path is empty
+    let is_progress_consistent = path.iter().all(|cell| visited.contains(cell))
+        && (!path.is_empty() || maze.all_cells().all(|cell| visited.contains(&cell)));
+
+    if !is_progress_consistent {
+        return Err(LoadError::InconsistentProgress);
+    }
+
+    // A new maze has every wall, so only the ones that were saved are kept
+    let all_cells: Vec<_> = maze.all_cells().collect();
+    for cell in all_cells {
+        maze.set_wall(cell, cell + (1, 0), false);
+        maze.set_wall(cell, cell + (0, 1), false);
+    }
+
+    for (cell1, cell2) in walls {
+        maze.set_wall(cell1, cell2, true);
+    }
+
+    // Each passage can only tunnel beneath a cell open on all four sides,
+    // or it would join the passage on top
+    for (cell, orientation) in under_passages {
+        if !maze.is_crossing(cell) {
+            return Err(LoadError::InvalidUnderPassage(cell));
+        }
+
+        maze.set_under_passage(cell, orientation);
+    }
+
+    maze.set_start(start);
+    maze.set_goal(goal);
+    maze.set_progress(visited, path);
+
+    Ok(SavedMaze {
+        maze,
+        seed: file.seed,
+        rng,
+    })
+}
+
+fn parse_mask(file: &MazeFile) -> Result<Mask, LoadError> {
+    let has_size = file.width > 0
+        && file.height > 0
+        && file.mask.len() == file.height as usize
+        && file
+            .mask
+            .iter()
+            .all(|row| row.chars().count() == file.width as usize);
+
+    if !has_size {
+        return Err(LoadError::InvalidMask);
+    }
+
+    let enabled = file
+        .mask
+        .iter()
+        .flat_map(|row| row.chars())
+        .map(|c| match c {
+            '#' => Ok(true),
+            '.' => Ok(false),
+            _ => Err(LoadError::InvalidMask),
+        })
+        .collect::<Result<_, _>>()?;
+
+    Mask::from_cells(file.width, file.height, enabled).map_err(|_| LoadError::InvalidMask)
+}
+
+fn parse_rng(rng_file: &RngFile) -> Result<ChaCha8Rng, LoadError> {
+    let bytes = rng_file.seed.as_bytes();
+
+    if bytes.len() != 64 || !rng_file.seed.is_ascii() {
+        return Err(LoadError::InvalidRng);
+    }
+
+    let mut seed = [0; 32];
+    for (byte, hex) in seed.iter_mut().zip(bytes.chunks(2)) {
+        let hex = std::str::from_utf8(hex).map_err(|_| LoadError::InvalidRng)?;
+        *byte = u8::from_str_radix(hex, 16).map_err(|_| LoadError::InvalidRng)?;
+    }
+
+    let mut rng = ChaCha8Rng::from_seed(seed);
+    rng.set_stream(rng_file.stream);
+    rng.set_word_pos(rng_file.word_pos);
+
+    Ok(rng)
+}
+
+fn point(cell: MazePoint) -> (i64, i64) {
+    (cell.x, cell.y)
+}
+
+#[derive(Serialize, Deserialize)]
+struct MazeFile {
+    version: u64,
+    width: i64,
+    height: i64,
+    /// One string per row, with `#` for enabled cells and `.` for disabled cells
+    mask: Vec<String>,
+    topology: String,
+    algorithm: String,
+    seed: u64,
+    rng: RngFile,
+    start: (i64, i64),
+    goal: (i64, i64),
+    walls: Vec<((i64, i64), (i64, i64))>,
+    under_passages: Vec<UnderPassageFile>,
+    visited: Vec<(i64, i64)>,
+    path: Vec<(i64, i64)>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct RngFile {
+    /// The 32 bytes of the ChaCha seed, in hex
+    seed: String,
+    stream: u64,
+    word_pos: u128,
+}
+
+#[derive(Serialize, Deserialize)]
+struct UnderPassageFile {
+    cell: (i64, i64),
+    orientation: String,
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    Json(serde_json::Error),
+    MissingVersion,
+    UnsupportedVersion(u64),
+    InvalidName {
+        field: &'static str,
+        value: String,
+    },
+    InvalidMask,
+    InvalidRng,
+    CellOutsideMaze {
+        field: &'static str,
+        cell: MazePoint,
+    },
+    WallNotBetweenNeighbours(MazePoint, MazePoint),
+    InvalidUnderPassage(MazePoint),
+    InconsistentProgress,
+}
+
+impl LoadError {
+    fn invalid_name(field: &'static str, value: &str) -> Self {
+        Self::InvalidName {
+            field,
+            value: value.to_string(),
+        }
+    }
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Json(e) => write!(f, "Malformed maze file: {}", e),
+            Self::MissingVersion => write!(f, "Not a maze file: it has no format version"),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "Unsupported maze file version {}, only version {} can be loaded",
+                version, FORMAT_VERSION
+            ),
+            Self::InvalidName { field, value } => {
+                write!(f, "Invalid value '{}' for '{}'", value, field)
+            }
+            Self::InvalidMask => write!(
+                f,
+                "The mask must have 'height' rows of 'width' '#' and '.' characters, \
+                 with at least one '#'"
+            ),
+            Self::InvalidRng => write!(f, "The RNG seed must be 64 hex digits"),
+            Self::CellOutsideMaze { field, cell } => write!(
+                f,
+                "Cell ({}, {}) in '{}' isn't part of the maze",
+                cell.x, cell.y, field
+            ),
+            Self::WallNotBetweenNeighbours(cell1, cell2) => write!(
+                f,
+                "Wall between ({}, {}) and ({}, {}) isn't between neighbouring cells",
+                cell1.x, cell1.y, cell2.x, cell2.y
+            ),
+            Self::InvalidUnderPassage(cell) => write!(
+                f,
+                "Under-passage at ({}, {}) isn't beneath a crossing that's open on all four sides",
+                cell.x, cell.y
+            ),
+            Self::InconsistentProgress => write!(
+                f,
+                "The generation's path must only contain visited cells, \
+                 and may only be empty once every cell is visited"
+            ),
+        }
+    }
+}
+
+impl Error for LoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Json(e) => Some(e),
+
+            Self::MissingVersion
+            | Self::UnsupportedVersion(_)
+            | Self::InvalidName { .. }
+            | Self::InvalidMask
+            | Self::InvalidRng
+            | Self::CellOutsideMaze { .. }
+            | Self::WallNotBetweenNeighbours(..)
+            | Self::InvalidUnderPassage(_)
+            | Self::InconsistentProgress => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, Snapshot};
+
+    /// A valid save of a half-generated maze, as JSON that can be tampered with
+    fn saved_value() -> Value {
+        let mut rng = ChaCha8Rng::seed_from_u64(5);
+        let mut maze = Maze::new(6, 4, Topology::Orthogonal, Algorithm::RecursiveBacktracker);
+
+        for _ in 0..10 {
+            maze.step(&mut rng);
+        }
+
+        serde_json::from_str(&to_json(&maze, 5, &rng)).unwrap()
+    }
+
+    fn load_value(value: &Value) -> Result<SavedMaze, LoadError> {
+        from_json(&value.to_string())
+    }
+
+    #[test]
+    fn resuming_a_saved_generation_gives_the_same_maze() {
+        let masks = [Mask::full(9, 7), testing::ring_mask()];
+
+        for &algorithm in Algorithm::all() {
+            for &topology in Topology::all() {
+                for mask in &masks {
+                    let seed = 11;
+                    let mut uninterrupted = Maze::with_mask(mask.clone(), topology, algorithm);
+                    uninterrupted.finish(&mut ChaCha8Rng::seed_from_u64(seed));
+
+                    let mut rng = ChaCha8Rng::seed_from_u64(seed);
+                    let mut interrupted = Maze::with_mask(mask.clone(), topology, algorithm);
+
+                    for _ in 0..20 {
+                        interrupted.step(&mut rng);
+                    }
+
+                    let json = to_json(&interrupted, seed, &rng);
+                    let mut saved = from_json(&json).unwrap();
+
+                    assert_eq!(saved.seed, seed);
+                    assert_eq!(Snapshot::of(&saved.maze), Snapshot::of(&interrupted));
+
+                    saved.maze.finish(&mut saved.rng);
+
+                    assert_eq!(
+                        Snapshot::of(&saved.maze),
+                        Snapshot::of(&uninterrupted),
+                        "{} {}",
+                        algorithm.name(),
+                        topology.name()
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn the_same_maze_is_always_saved_the_same_way() {
+        let maze = testing::generated(8, 8, Topology::Weave, 2);
+        let rng = ChaCha8Rng::seed_from_u64(2);
+        let json = to_json(&maze, 2, &rng);
+
+        let saved = from_json(&json).unwrap();
+
+        assert_eq!(to_json(&saved.maze, saved.seed, &saved.rng), json);
+    }
+
+    #[test]
+    fn malformed_json_is_an_error() {
+        assert!(matches!(from_json("{"), Err(LoadError::Json(_))));
+
+        let mut value = saved_value();
+        value.as_object_mut().unwrap().remove("walls");
+
+        assert!(matches!(load_value(&value), Err(LoadError::Json(_))));
+    }
+
+    #[test]
+    fn other_versions_are_rejected() {
+        let mut value = saved_value();
+
+        value.as_object_mut().unwrap().remove("version");
+        assert!(matches!(load_value(&value), Err(LoadError::MissingVersion)));
+
+        value["version"] = (FORMAT_VERSION + 1).into();
+        assert!(matches!(
+            load_value(&value),
+            Err(LoadError::UnsupportedVersion(version)) if version == FORMAT_VERSION + 1
+        ));
+    }
+
+    #[test]
+    fn invalid_fields_are_rejected() {
+        let tamper = |field: &str, new_value: Value| {
+            let mut value = saved_value();
+            value[field] = new_value;
+
+            load_value(&value).err()
+        };
+
+        assert!(matches!(
+            tamper("algorithm", "sidewinder".into()),
+            Some(LoadError::InvalidName {
+                field: "algorithm",
+                ..
+            })
+        ));
+        assert!(matches!(
+            tamper("mask", serde_json::json!(["######"])),
+            Some(LoadError::InvalidMask)
+        ));
+        assert!(matches!(
+            tamper(
+                "rng",
+                serde_json::json!({"seed": "zz", "stream": 0, "word_pos": 0})
+            ),
+            Some(LoadError::InvalidRng)
+        ));
+        assert!(matches!(
+            tamper("goal", serde_json::json!([6, 0])),
+            Some(LoadError::CellOutsideMaze { field: "goal", .. })
+        ));
+        assert!(matches!(
+            tamper("walls", serde_json::json!([[[0, 0], [1, 1]]])),
+            Some(LoadError::WallNotBetweenNeighbours(..))
+        ));
+        assert!(matches!(
+            tamper("path", serde_json::json!([])),
+            Some(LoadError::InconsistentProgress)
+        ));
+        assert!(matches!(
+            tamper("visited", serde_json::json!([])),
+            Some(LoadError::InconsistentProgress)
+        ));
+    }
+
+    #[test]
+    fn under_passages_must_be_beneath_crossings() {
+        let maze = testing::generated(8, 8, Topology::Weave, 2);
+        let (cell, _) = maze.under_passages().next().unwrap();
+        let mut value: Value =
+            serde_json::from_str(&to_json(&maze, 2, &ChaCha8Rng::seed_from_u64(2))).unwrap();
+
+        // Wall off one side of the crossing, as if the file had been edited by hand
+        value["walls"]
+            .as_array_mut()
+            .unwrap()
+            .push(serde_json::json!([point(cell), point(cell + (1, 0))]));
+
+        assert!(matches!(
+            load_value(&value),
+            Err(LoadError::InvalidUnderPassage(invalid)) if invalid == cell
+        ));
+    }
+}
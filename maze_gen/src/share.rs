@@ -0,0 +1,504 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+
+use crate::mask::Mask;
+use crate::maze::{Algorithm, Maze, Orientation, Topology};
+use crate::point::MazePoint;
+
+/// Changes whenever the format does, so that older codes are rejected instead of misread
+pub const FORMAT_VERSION: u8 = 1;
+
+/// The widest or highest maze a code can hold, so a tampered code can't ask for a huge maze
+pub const MAX_SIZE: i64 = 1024;
+
+const HEADER_LENGTH: usize = 15;
+
+const FLAG_WEAVE: u8 = 1 << 0;
+const FLAG_MASK: u8 = 1 << 1;
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Encodes a finished maze as compactly as possible
+///
+/// The header holds the version, flags for the topology and whether there's a mask,
+/// the algorithm, the size as two little-endian `u16`s, and the start and goal
+/// as little-endian `u32` cell indices. It's followed by one bit per cell for the mask,
+/// if there is one, one bit per wall to the right of and below every cell,
+/// and for a weave maze, two bits per cell for the passage tunnelling beneath it.
+///
+/// Sizes are limited to [`MAX_SIZE`] cells a side. The generator's progress isn't kept,
+/// since a shared maze is always finished.
+pub fn to_bytes(maze: &Maze) -> Result<Vec<u8>, ShareError> {
+    let width = maze.width();
+    let height = maze.height();
+
+    if width > MAX_SIZE || height > MAX_SIZE {
+        return Err(ShareError::TooLarge { width, height });
+    }
+
+    let is_masked = maze.all_cells().count() as i64 != width * height;
+
+    let mut flags = 0;
+    if maze.topology() == Topology::Weave {
+        flags |= FLAG_WEAVE;
+    }
+    if is_masked {
+        flags |= FLAG_MASK;
+    }
+
+    let algorithm = Algorithm::all()
+        .iter()
+        .position(|&algorithm| algorithm == maze.algorithm())
+        .unwrap() as u8;
+
+    let mut bytes = vec![FORMAT_VERSION, flags, algorithm];
+    bytes.extend_from_slice(&(width as u16).to_le_bytes());
+    bytes.extend_from_slice(&(height as u16).to_le_bytes());
+    bytes.extend_from_slice(&(cell_index(maze, maze.start()) as u32).to_le_bytes());
+    bytes.extend_from_slice(&(cell_index(maze, maze.goal()) as u32).to_le_bytes());
+
+    let mut bits = BitWriter::new(bytes);
+    let cells: Vec<MazePoint> = (0..height)
+        .flat_map(|y| (0..width).map(move |x| MazePoint::new(x, y)))
+        .collect();
+
+    if is_masked {
+        for &cell in &cells {
+            bits.push(maze.is_point_inside(cell));
+        }
+    }
+
+    for &cell in &cells {
+        if cell.x < width - 1 {
+            bits.push(has_wall(maze, cell, cell + (1, 0)));
+        }
+
+        if cell.y < height - 1 {
+            bits.push(has_wall(maze, cell, cell + (0, 1)));
+        }
+    }
+
+    if maze.topology() == Topology::Weave {
+        for &cell in &cells {
+            let (high, low) = match maze.under_passage(cell) {
+                None => (false, false),
+                Some(Orientation::Horizontal) => (false, true),
+                Some(Orientation::Vertical) => (true, false),
+            };
+
+            bits.push(high);
+            bits.push(low);
+        }
+    }
+
+    Ok(bits.into_bytes())
+}
+
+/// Decodes a maze encoded by [`to_bytes`]
+pub fn from_bytes(bytes: &[u8]) -> Result<Maze, ShareError> {
+    if bytes.len() < HEADER_LENGTH {
+        return Err(ShareError::Truncated);
+    }
+
+    if bytes[0] != FORMAT_VERSION {
+        return Err(ShareError::UnsupportedVersion(bytes[0]));
+    }
+
+    let flags = bytes[1];
+    let algorithm = *Algorithm::all()
+        .get(bytes[2] as usize)
+        .ok_or(ShareError::InvalidHeader)?;
+    let width = u16::from_le_bytes([bytes[3], bytes[4]]) as i64;
+    let height = u16::from_le_bytes([bytes[5], bytes[6]]) as i64;
+    let start = u32::from_le_bytes([bytes[7], bytes[8], bytes[9], bytes[10]]) as i64;
+    let goal = u32::from_le_bytes([bytes[11], bytes[12], bytes[13], bytes[14]]) as i64;
+
+    if width == 0 || height == 0 {
+        return Err(ShareError::InvalidHeader);
+    }
+
+    if width > MAX_SIZE || height > MAX_SIZE {
+        return Err(ShareError::TooLarge { width, height });
+    }
+
+    let topology = if flags & FLAG_WEAVE != 0 {
+        Topology::Weave
+    } else {
+        Topology::Orthogonal
+    };
+
+    // Checked up front, so a code that's cut short fails before the maze is made
+    let cell_count = (width * height) as usize;
+    let mut bit_count =
+        (width - 1) as usize * height as usize + width as usize * (height - 1) as usize;
+    if flags & FLAG_MASK != 0 {
+        bit_count += cell_count;
+    }
+    if topology == Topology::Weave {
+        bit_count += 2 * cell_count;
+    }
+
+    let payload_length = bytes.len() - HEADER_LENGTH;
+
+    if payload_length * 8 < bit_count {
+        return Err(ShareError::Truncated);
+    }
+
+    if payload_length > bit_count.div_ceil(8) {
+        return Err(ShareError::TrailingBytes);
+    }
+
+    let mut bits = BitReader::new(&bytes[HEADER_LENGTH..]);
+    let cells: Vec<MazePoint> = (0..height)
+        .flat_map(|y| (0..width).map(move |x| MazePoint::new(x, y)))
+        .collect();
+
+    let mask = if flags & FLAG_MASK != 0 {
+        let enabled = cells
+            .iter()
+            .map(|_| bits.next())
+            .collect::<Result<_, _>>()?;
+
+        Mask::from_cells(width, height, enabled).map_err(|_| ShareError::InvalidMask)?
+    } else {
+        Mask::full(width, height)
+    };
+
+    let mut maze = Maze::with_mask(mask, topology, algorithm);
+
+    for &cell in &cells {
+        if cell.x < width - 1 {
+            maze.set_wall(cell, cell + (1, 0), bits.next()?);
+        }
+
+        if cell.y < height - 1 {
+            maze.set_wall(cell, cell + (0, 1), bits.next()?);
+        }
+    }
+
+    if topology == Topology::Weave {
+        for &cell in &cells {
+            let orientation = match (bits.next()?, bits.next()?) {
+                (false, false) => None,
+                (false, true) => Some(Orientation::Horizontal),
+                (true, false) => Some(Orientation::Vertical),
+                (true, true) => return Err(ShareError::InvalidUnderPassage(cell)),
+            };
+
+            if let Some(orientation) = orientation {
+                if !maze.is_crossing(cell) {
+                    return Err(ShareError::InvalidUnderPassage(cell));
+                }
+
+                maze.set_under_passage(cell, orientation);
+            }
+        }
+    }
+
+    for (index, set_cell) in [
+        (start, Maze::set_start as fn(&mut Maze, MazePoint)),
+        (goal, Maze::set_goal),
+    ] {
+        let cell = MazePoint::new(index % width, index / width);
+
+        if !maze.is_point_inside(cell) {
+            return Err(ShareError::InvalidHeader);
+        }
+
+        set_cell(&mut maze, cell);
+    }
+
+    maze.mark_finished();
+
+    Ok(maze)
+}
+
+/// Encodes a finished maze as a short string that's safe to paste anywhere, using base64url
+pub fn to_code(maze: &Maze) -> Result<String, ShareError> {
+    let bytes = to_bytes(maze)?;
+    let mut code = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, &byte)| {
+            group | (byte as u32) << (16 - 8 * i)
+        });
+
+        // Every byte needs at least two characters, and there's no padding
+        for i in 0..=chunk.len() {
+            let index = (group >> (18 - 6 * i)) & 0x3F;
+            code.push(BASE64_ALPHABET[index as usize] as char);
+        }
+    }
+
+    Ok(code)
+}
+
+/// Decodes a code made by [`to_code`], ignoring any whitespace around or inside it
+pub fn from_code(code: &str) -> Result<Maze, ShareError> {
+    let values = code
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .map(|c| {
+            BASE64_ALPHABET
+                .iter()
+                .position(|&letter| letter as char == c)
+                .map(|value| value as u32)
+                .ok_or(ShareError::InvalidCharacter(c))
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut bytes = Vec::with_capacity(values.len() * 3 / 4);
+
+    for chunk in values.chunks(4) {
+        if chunk.len() == 1 {
+            return Err(ShareError::Truncated);
+        }
+
+        let group = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |group, (i, &value)| group | value << (18 - 6 * i));
+
+        for i in 0..chunk.len() - 1 {
+            bytes.push((group >> (16 - 8 * i)) as u8);
+        }
+    }
+
+    from_bytes(&bytes)
+}
+
+/// The index of a cell in row-major order
+fn cell_index(maze: &Maze, cell: MazePoint) -> i64 {
+    cell.y * maze.width() + cell.x
+}
+
+fn has_wall(maze: &Maze, cell1: MazePoint, cell2: MazePoint) -> bool {
+    maze.is_point_inside(cell1) && maze.is_point_inside(cell2) && !maze.is_passage(cell1, cell2)
+}
+
+/// Appends bits to a byte buffer, starting from the lowest bit of every byte
+struct BitWriter {
+    bytes: Vec<u8>,
+    bit: u8,
+}
+
+impl BitWriter {
+    fn new(bytes: Vec<u8>) -> Self {
+        Self { bytes, bit: 8 }
+    }
+
+    fn push(&mut self, value: bool) {
+        if self.bit == 8 {
+            self.bytes.push(0);
+            self.bit = 0;
+        }
+
+        if value {
+            *self.bytes.last_mut().unwrap() |= 1 << self.bit;
+        }
+
+        self.bit += 1;
+    }
+
+    fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+struct BitReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    fn next(&mut self) -> Result<bool, ShareError> {
+        let byte = self
+            .bytes
+            .get(self.position / 8)
+            .ok_or(ShareError::Truncated)?;
+        let bit = byte >> (self.position % 8) & 1;
+
+        self.position += 1;
+
+        Ok(bit == 1)
+    }
+}
+
+#[derive(Debug)]
+pub enum ShareError {
+    InvalidCharacter(char),
+    Truncated,
+    /// More bytes than the maze in the header needs
+    TrailingBytes,
+    UnsupportedVersion(u8),
+    InvalidHeader,
+    InvalidMask,
+    InvalidUnderPassage(MazePoint),
+    TooLarge {
+        width: i64,
+        height: i64,
+    },
+}
+
+impl Display for ShareError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::InvalidCharacter(c) => write!(f, "Invalid character '{}' in maze code", c),
+            Self::Truncated => write!(f, "Maze code is incomplete"),
+            Self::TrailingBytes => write!(f, "Maze code is longer than its maze"),
+            Self::UnsupportedVersion(version) => write!(
+                f,
+                "Unsupported maze code version {}, only version {} can be loaded",
+                version, FORMAT_VERSION
+            ),
+            Self::InvalidHeader => write!(f, "Maze code has an invalid size, start or goal"),
+            Self::InvalidMask => write!(f, "Maze code has no enabled cells"),
+            Self::InvalidUnderPassage(cell) => write!(
+                f,
+                "Maze code has an invalid under-passage at ({}, {})",
+                cell.x, cell.y
+            ),
+            Self::TooLarge { width, height } => write!(
+                f,
+                "A {}x{} maze is too large for a maze code, which holds up to {} cells a side",
+                width, height, MAX_SIZE
+            ),
+        }
+    }
+}
+
+impl Error for ShareError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, Snapshot};
+
+    fn assert_round_trips(maze: &Maze) {
+        let code = to_code(maze).unwrap();
+        let decoded = from_code(&code).unwrap();
+
+        assert_eq!(Snapshot::of(&decoded), Snapshot::of(maze));
+        assert!(decoded.algorithm() == maze.algorithm());
+        assert!(decoded.topology() == maze.topology());
+    }
+
+    /// A header for a maze of the given size, with nothing after it
+    fn header(width: u16, height: u16) -> Vec<u8> {
+        let mut bytes = vec![FORMAT_VERSION, 0, 0];
+        bytes.extend(width.to_le_bytes());
+        bytes.extend(height.to_le_bytes());
+        bytes.extend([0; 8]);
+
+        bytes
+    }
+
+    #[test]
+    fn codes_round_trip() {
+        for seed in 0..4 {
+            assert_round_trips(&testing::generated(13, 8, Topology::Orthogonal, seed));
+            assert_round_trips(&testing::masked(Topology::Orthogonal, seed));
+            assert_round_trips(&testing::masked(Topology::Weave, seed));
+
+            let weave = testing::generated(13, 8, Topology::Weave, seed);
+            assert!(weave.under_passages().count() > 0);
+            assert_round_trips(&weave);
+        }
+    }
+
+    #[test]
+    fn whitespace_in_codes_is_ignored() {
+        let maze = testing::generated(7, 5, Topology::Orthogonal, 1);
+        let code = to_code(&maze).unwrap();
+        let (first, second) = code.split_at(code.len() / 2);
+
+        let decoded = from_code(&format!("  {}\n{} \n", first, second)).unwrap();
+
+        assert_eq!(Snapshot::of(&decoded), Snapshot::of(&maze));
+    }
+
+    #[test]
+    fn truncated_codes_are_errors() {
+        let code = to_code(&testing::generated(9, 9, Topology::Weave, 2)).unwrap();
+
+        for length in [0, 1, 10, 19, code.len() - 8] {
+            assert!(matches!(
+                from_code(&code[..length]),
+                Err(ShareError::Truncated)
+            ));
+        }
+    }
+
+    #[test]
+    fn codes_with_extra_bytes_are_errors() {
+        let maze = testing::generated(9, 9, Topology::Weave, 2);
+        let mut bytes = to_bytes(&maze).unwrap();
+        bytes.push(0);
+
+        assert!(matches!(from_bytes(&bytes), Err(ShareError::TrailingBytes)));
+    }
+
+    #[test]
+    fn garbled_codes_are_errors() {
+        let code = to_code(&testing::generated(9, 9, Topology::Orthogonal, 2)).unwrap();
+        let garbled = code.replacen(|_| true, "!", 1);
+
+        assert!(matches!(
+            from_code(&garbled),
+            Err(ShareError::InvalidCharacter('!'))
+        ));
+
+        let mut bytes = to_bytes(&testing::generated(9, 9, Topology::Orthogonal, 2)).unwrap();
+        bytes[0] = FORMAT_VERSION + 1;
+
+        assert!(matches!(
+            from_bytes(&bytes),
+            Err(ShareError::UnsupportedVersion(version)) if version == FORMAT_VERSION + 1
+        ));
+    }
+
+    #[test]
+    fn headers_larger_than_the_payload_are_errors() {
+        assert!(matches!(
+            from_bytes(&header(u16::MAX, u16::MAX)),
+            Err(ShareError::TooLarge { .. })
+        ));
+        assert!(matches!(
+            from_bytes(&header(MAX_SIZE as u16, MAX_SIZE as u16)),
+            Err(ShareError::Truncated)
+        ));
+        assert!(matches!(
+            from_bytes(&header(0, 10)),
+            Err(ShareError::InvalidHeader)
+        ));
+    }
+
+    #[test]
+    fn mazes_too_large_to_share_are_errors() {
+        let maze = Maze::new(
+            MAX_SIZE + 1,
+            1,
+            Topology::Orthogonal,
+            Algorithm::RecursiveBacktracker,
+        );
+
+        assert!(matches!(to_code(&maze), Err(ShareError::TooLarge { .. })));
+    }
+
+    #[test]
+    fn under_passages_must_be_beneath_crossings() {
+        // No cell of a 2x2 maze can be a crossing, so give the first one an under-passage
+        let mut bytes = to_bytes(&testing::generated(2, 2, Topology::Weave, 0)).unwrap();
+        bytes[HEADER_LENGTH] |= 1 << 5;
+
+        assert!(matches!(
+            from_bytes(&bytes),
+            Err(ShareError::InvalidUnderPassage(cell)) if cell == MazePoint::new(0, 0)
+        ));
+    }
+}
@@ -0,0 +1,222 @@
+use std::collections::hash_map::Entry;
+use std::collections::{HashMap, VecDeque};
+
+use crate::maze::{Maze, Orientation};
+use crate::point::MazePoint;
+
+const DIRECTIONS: [(i64, i64); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+/// The number of moves it takes to reach every reachable cell from `from`
+///
+/// Going through an under-passage counts as a single move, and doesn't reach the cell above it.
+pub fn distances(maze: &Maze, from: MazePoint) -> HashMap<MazePoint, usize> {
+    search(maze, from).0
+}
+
+/// The directions that can be moved in from `cell`, as offsets like `(1, 0)` for right
+pub fn open_directions(maze: &Maze, cell: MazePoint) -> Vec<(i64, i64)> {
+    DIRECTIONS
+        .into_iter()
+        .filter(|&dir| move_towards(maze, cell, dir).is_some())
+        .collect()
+}
+
+/// A way of finding a path from the start of a maze to its goal
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub enum Solver {
+    /// The shortest path, found by a breadth-first search
+    #[default]
+    ShortestPath,
+    /// Every move of someone keeping their left hand on the wall, dead ends and all
+    WallFollower,
+}
+
+impl Solver {
+    pub fn all() -> &'static [Solver] {
+        &[Solver::ShortestPath, Solver::WallFollower]
+    }
+
+    /// A short name, for use in command-line arguments and files
+    pub fn name(&self) -> &'static str {
+        match self {
+            Solver::ShortestPath => "shortest",
+            Solver::WallFollower => "wall-follower",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all()
+            .iter()
+            .copied()
+            .find(|solver| solver.name() == name)
+    }
+}
+
+/// The path a solver takes from the start of the maze to its goal, including both,
+/// or `None` if it doesn't reach the goal
+pub fn solve(maze: &Maze, solver: Solver) -> Option<Vec<MazePoint>> {
+    match solver {
+        Solver::ShortestPath => solution(maze),
+        Solver::WallFollower => follow_wall(maze),
+    }
+}
+
+/// The shortest path from the start of the maze to its goal, including both,
+/// or `None` if the goal can't be reached
+///
+/// Consecutive cells of the path are adjacent, except on either side of an under-passage.
+pub fn solution(maze: &Maze) -> Option<Vec<MazePoint>> {
+    let (_, previous) = search(maze, maze.start());
+
+    if !previous.contains_key(&maze.goal()) {
+        return None;
+    }
+
+    let mut path = vec![maze.goal()];
+    let mut cell = maze.goal();
+
+    while cell != maze.start() {
+        cell = previous[&cell];
+        path.push(cell);
+    }
+
+    path.reverse();
+
+    Some(path)
+}
+
+/// Walks from the start to the goal by always taking the leftmost way out of every cell
+///
+/// The path goes back over itself out of every dead end. A masked maze whose goal is
+/// in another part than the start never reaches it, which is detected by running out of moves,
+/// since every cell can only be left in each direction once before the walk repeats itself.
+fn follow_wall(maze: &Maze) -> Option<Vec<MazePoint>> {
+    let (start, goal) = (maze.start(), maze.goal());
+
+    if !maze.is_point_inside(start) {
+        return None;
+    }
+
+    let max_moves = maze.all_cells().count() * DIRECTIONS.len();
+
+    let mut path = vec![start];
+    let mut cell = start;
+    let mut facing = (1, 0);
+
+    while cell != goal {
+        if path.len() > max_moves {
+            return None;
+        }
+
+        // Left, straight ahead, right and then back, where y points down
+        let (x, y) = facing;
+        let turns = [(y, -x), (x, y), (-y, x), (-x, -y)];
+
+        let (dir, next) = turns
+            .into_iter()
+            .find_map(|dir| move_towards(maze, cell, dir).map(|next| (dir, next)))?;
+
+        facing = dir;
+        cell = next;
+        path.push(cell);
+    }
+
+    Some(path)
+}
+
+/// Breadth-first search from `from`, returning the distance to every reachable cell
+/// and the cell it's reached from (`from` itself is reached from itself)
+fn search(
+    maze: &Maze,
+    from: MazePoint,
+) -> (HashMap<MazePoint, usize>, HashMap<MazePoint, MazePoint>) {
+    let mut distances = HashMap::new();
+    let mut previous = HashMap::new();
+    let mut queue = VecDeque::new();
+
+    if maze.is_point_inside(from) {
+        distances.insert(from, 0);
+        previous.insert(from, from);
+        queue.push_back(from);
+    }
+
+    while let Some(cell) = queue.pop_front() {
+        let distance = distances[&cell];
+
+        for next in moves(maze, cell) {
+            if let Entry::Vacant(entry) = distances.entry(next) {
+                entry.insert(distance + 1);
+                previous.insert(next, cell);
+                queue.push_back(next);
+            }
+        }
+    }
+
+    (distances, previous)
+}
+
+/// The cells that can be reached from `cell` in one move
+///
+/// A cell with a passage beneath it can only be crossed along its passage on top,
+/// and moving towards an under-passage goes all the way through it.
+fn moves(maze: &Maze, cell: MazePoint) -> Vec<MazePoint> {
+    DIRECTIONS
+        .into_iter()
+        .filter_map(|dir| move_towards(maze, cell, dir))
+        .collect()
+}
+
+/// The cell reached by moving from `cell` in a direction, if there's a way through
+fn move_towards(maze: &Maze, cell: MazePoint, dir: (i64, i64)) -> Option<MazePoint> {
+    if maze.under_passage(cell) == Some(orientation_of(dir)) || !maze.is_passage(cell, cell + dir) {
+        return None;
+    }
+
+    let next = cell + dir;
+
+    if maze.under_passage(next) == Some(orientation_of(dir)) {
+        // A wall on the far side of a crossing blocks the passage beneath it too
+        maze.is_passage(next, next + dir).then_some(next + dir)
+    } else {
+        Some(next)
+    }
+}
+
+fn orientation_of(dir: (i64, i64)) -> Orientation {
+    if dir.1 == 0 {
+        Orientation::Horizontal
+    } else {
+        Orientation::Vertical
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::Topology;
+    use crate::testing;
+
+    #[test]
+    fn walls_beside_crossings_block_the_passage_beneath() {
+        let maze = testing::generated(12, 9, Topology::Weave, 1);
+        let crossings: Vec<_> = maze.under_passages().collect();
+        assert!(!crossings.is_empty());
+
+        for (cell, orientation) in crossings {
+            let dir = match orientation {
+                Orientation::Horizontal => (1, 0),
+                Orientation::Vertical => (0, 1),
+            };
+            let (before, after) = (cell + (-dir.0, -dir.1), cell + dir);
+
+            assert!(moves(&maze, before).contains(&after));
+            assert!(moves(&maze, after).contains(&before));
+
+            let mut blocked = testing::generated(12, 9, Topology::Weave, 1);
+            blocked.set_wall(cell, after, true);
+
+            assert!(!moves(&blocked, before).contains(&after));
+            assert!(!moves(&blocked, after).contains(&before));
+        }
+    }
+}
@@ -0,0 +1,79 @@
+//! Mazes and comparisons shared by the tests of the other modules
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+
+use crate::mask::Mask;
+use crate::maze::{Algorithm, Maze, Topology};
+
+type Cell = (i64, i64);
+
+/// Everything about a maze that saving, sharing or stepping back and forth should keep,
+/// in a form that can be compared and printed by `assert_eq!`
+#[derive(Debug, PartialEq, Eq)]
+pub struct Snapshot {
+    width: i64,
+    height: i64,
+    cells: BTreeSet<Cell>,
+    walls: BTreeSet<(Cell, Cell)>,
+    under_passages: BTreeMap<Cell, &'static str>,
+    visited: BTreeSet<Cell>,
+    path: Vec<Cell>,
+    start: Cell,
+    goal: Cell,
+}
+
+impl Snapshot {
+    pub fn of(maze: &Maze) -> Self {
+        Self {
+            width: maze.width(),
+            height: maze.height(),
+            cells: maze.all_cells().map(Cell::from).collect(),
+            walls: maze
+                .walls()
+                .map(|wall| (wall.first_cell().into(), wall.second_cell().into()))
+                .collect(),
+            under_passages: maze
+                .under_passages()
+                .map(|(cell, orientation)| (cell.into(), orientation.name()))
+                .collect(),
+            visited: maze.visited_cells().map(Cell::from).collect(),
+            path: maze.path().iter().copied().map(Cell::from).collect(),
+            start: maze.start().into(),
+            goal: maze.goal().into(),
+        }
+    }
+}
+
+/// A finished maze, the same one every time for the same arguments
+pub fn generated(width: i64, height: i64, topology: Topology, seed: u64) -> Maze {
+    let mut maze = Maze::new(width, height, topology, Algorithm::RecursiveBacktracker);
+    maze.finish(&mut ChaCha8Rng::seed_from_u64(seed));
+
+    maze
+}
+
+/// A mask shaped like a ring, with a hole in the middle and its corners cut off
+pub fn ring_mask() -> Mask {
+    Mask::from_ascii(
+        "\
+..######..
+.########.
+####..####
+####..####
+.########.
+..######..
+",
+    )
+    .unwrap()
+}
+
+/// A finished maze shaped by [`ring_mask`]
+pub fn masked(topology: Topology, seed: u64) -> Maze {
+    let mut maze = Maze::with_mask(ring_mask(), topology, Algorithm::RecursiveBacktracker);
+    maze.finish(&mut ChaCha8Rng::seed_from_u64(seed));
+
+    maze
+}
@@ -0,0 +1,502 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+
+use crate::mask::Mask;
+use crate::maze::{Algorithm, Maze, Orientation, Topology};
+use crate::point::MazePoint;
+
+/// The characters a maze is drawn with as text
+///
+/// Both styles share the same layout: every cell is two characters wide, so the result is
+/// `3 * width + 1` characters wide and `2 * height + 1` lines high.
+/// The start and goal are marked with `S` and `G`, cells that are masked out are filled in,
+/// and cells with a passage tunnelling beneath them show the direction of the passage above.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum TextStyle {
+    /// `+--+` for horizontal walls and `|` for vertical walls
+    Ascii,
+    /// Box-drawing characters, with corners that join up with the walls around them
+    Unicode,
+}
+
+impl TextStyle {
+    pub fn all() -> &'static [TextStyle] {
+        &[TextStyle::Ascii, TextStyle::Unicode]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            TextStyle::Ascii => "ascii",
+            TextStyle::Unicode => "unicode",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all()
+            .iter()
+            .copied()
+            .find(|style| style.name() == name)
+    }
+
+    fn disabled_cell(&self) -> char {
+        match self {
+            TextStyle::Ascii => '.',
+            TextStyle::Unicode => '░',
+        }
+    }
+
+    /// The marker for a cell whose passage on top goes in the given orientation
+    fn over_passage(&self, orientation: Orientation) -> char {
+        match (self, orientation) {
+            (TextStyle::Ascii, Orientation::Vertical) => '|',
+            (TextStyle::Ascii, Orientation::Horizontal) => '=',
+            (TextStyle::Unicode, Orientation::Vertical) => '║',
+            (TextStyle::Unicode, Orientation::Horizontal) => '═',
+        }
+    }
+
+    fn horizontal_wall(&self) -> char {
+        match self {
+            TextStyle::Ascii => '-',
+            TextStyle::Unicode => '─',
+        }
+    }
+
+    fn vertical_wall(&self) -> char {
+        match self {
+            TextStyle::Ascii => '|',
+            TextStyle::Unicode => '│',
+        }
+    }
+
+    /// The character where walls meet, given which of its four sides have a wall
+    fn corner(&self, up: bool, down: bool, left: bool, right: bool) -> char {
+        match self {
+            TextStyle::Ascii => {
+                if up || down || left || right {
+                    '+'
+                } else {
+                    ' '
+                }
+            }
+
+            TextStyle::Unicode => match (up, down, left, right) {
+                (false, false, false, false) => ' ',
+                (true, false, false, false) => '╵',
+                (false, true, false, false) => '╷',
+                (false, false, true, false) => '╴',
+                (false, false, false, true) => '╶',
+                (true, true, false, false) => '│',
+                (false, false, true, true) => '─',
+                (false, true, false, true) => '┌',
+                (false, true, true, false) => '┐',
+                (true, false, false, true) => '└',
+                (true, false, true, false) => '┘',
+                (true, true, false, true) => '├',
+                (true, true, true, false) => '┤',
+                (false, true, true, true) => '┬',
+                (true, false, true, true) => '┴',
+                (true, true, true, true) => '┼',
+            },
+        }
+    }
+}
+
+/// Renders the maze as text in the given style, which [`parse`] reads back into the same maze
+pub fn render(maze: &Maze, style: TextStyle) -> String {
+    let mut text = String::new();
+
+    for y in 0..=maze.height() {
+        for x in 0..=maze.width() {
+            let cell = MazePoint::new(x, y);
+
+            text.push(style.corner(
+                has_wall(maze, cell + (-1, -1), cell + (0, -1)),
+                has_wall(maze, cell + (-1, 0), cell),
+                has_wall(maze, cell + (-1, -1), cell + (-1, 0)),
+                has_wall(maze, cell + (0, -1), cell),
+            ));
+
+            if x < maze.width() {
+                let wall = if has_wall(maze, cell + (0, -1), cell) {
+                    style.horizontal_wall()
+                } else {
+                    ' '
+                };
+
+                text.push(wall);
+                text.push(wall);
+            }
+        }
+
+        text.push('\n');
+
+        if y < maze.height() {
+            for x in 0..=maze.width() {
+                let cell = MazePoint::new(x, y);
+
+                let has_left_wall = has_wall(maze, cell + (-1, 0), cell);
+                text.push(if has_left_wall {
+                    style.vertical_wall()
+                } else {
+                    ' '
+                });
+
+                if x < maze.width() {
+                    let [first, second] = cell_interior(maze, cell, style);
+
+                    text.push(first);
+                    text.push(second);
+                }
+            }
+
+            text.push('\n');
+        }
+    }
+
+    text
+}
+
+/// Reads a maze drawn by [`render`], in either style
+///
+/// Any character other than a space counts as a wall, so mazes drawn by hand are accepted too.
+/// Lines may be missing their trailing spaces. If there's no `S` or `G`,
+/// the start or goal is left where a new maze would have it.
+///
+/// The maze comes back fully generated, with the weave topology if it has any under-passages.
+pub fn parse(text: &str) -> Result<Maze, ParseError> {
+    let mut lines: Vec<Vec<char>> = text
+        .lines()
+        .map(|line| line.trim_end_matches('\r').chars().collect())
+        .collect();
+
+    while lines
+        .last()
+        .is_some_and(|line| line.iter().all(|&c| c == ' '))
+    {
+        lines.pop();
+    }
+
+    let longest_line = lines.iter().map(Vec::len).max().unwrap_or(0);
+    let width = (longest_line as i64 + 1) / 3;
+    let height = lines.len() as i64 / 2;
+
+    if width == 0 || height == 0 {
+        return Err(ParseError::Empty);
+    }
+
+    let char_at = |column: i64, line: i64| {
+        lines
+            .get(line as usize)
+            .and_then(|line| line.get(column as usize))
+            .copied()
+            .unwrap_or(' ')
+    };
+
+    let mut enabled = Vec::new();
+    let mut start = None;
+    let mut goal = None;
+    let mut under_passages = Vec::new();
+
+    for y in 0..height {
+        for x in 0..width {
+            let cell = MazePoint::new(x, y);
+            let line = 2 * y + 1;
+            let columns = [3 * x + 1, 3 * x + 2];
+            let interior = columns.map(|column| char_at(column, line));
+
+            let is_disabled = interior.iter().all(|&c| {
+                TextStyle::all()
+                    .iter()
+                    .any(|style| c == style.disabled_cell())
+            });
+            enabled.push(!is_disabled);
+
+            if is_disabled {
+                continue;
+            }
+
+            for (column, c) in columns.into_iter().zip(interior) {
+                let marker = match c {
+                    ' ' => None,
+                    'S' => Some(&mut start),
+                    'G' => Some(&mut goal),
+
+                    _ => {
+                        let orientation = [Orientation::Horizontal, Orientation::Vertical]
+                            .into_iter()
+                            .find(|&orientation| {
+                                TextStyle::all()
+                                    .iter()
+                                    .any(|style| c == style.over_passage(orientation))
+                            })
+                            .ok_or(ParseError::UnexpectedCharacter {
+                                line: line as usize + 1,
+                                column: column as usize + 1,
+                                character: c,
+                            })?;
+
+                        // The passage underneath goes the other way
+                        let under = match orientation {
+                            Orientation::Horizontal => Orientation::Vertical,
+                            Orientation::Vertical => Orientation::Horizontal,
+                        };
+
+                        if !under_passages.contains(&(cell, under)) {
+                            under_passages.push((cell, under));
+                        }
+
+                        None
+                    }
+                };
+
+                if let Some(marker) = marker {
+                    if marker.replace(cell).is_some_and(|other| other != cell) {
+                        return Err(if c == 'S' {
+                            ParseError::MultipleStarts
+                        } else {
+                            ParseError::MultipleGoals
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    let mask = Mask::from_cells(width, height, enabled).map_err(|_| ParseError::Empty)?;
+    let topology = if under_passages.is_empty() {
+        Topology::Orthogonal
+    } else {
+        Topology::Weave
+    };
+
+    let mut maze = Maze::with_mask(mask, topology, Algorithm::RecursiveBacktracker);
+
+    for y in 0..height {
+        for x in 0..width {
+            let cell = MazePoint::new(x, y);
+
+            let right_wall = char_at(3 * x + 3, 2 * y + 1);
+            maze.set_wall(cell, cell + (1, 0), right_wall != ' ');
+
+            let bottom_wall = [char_at(3 * x + 1, 2 * y + 2), char_at(3 * x + 2, 2 * y + 2)];
+            maze.set_wall(cell, cell + (0, 1), bottom_wall != [' ', ' ']);
+        }
+    }
+
+    for &(cell, orientation) in &under_passages {
+        if !maze.is_crossing(cell)
+            || under_passages
+                .iter()
+                .any(|&(other, o)| other == cell && o != orientation)
+        {
+            return Err(ParseError::InvalidUnderPassage(cell));
+        }
+
+        maze.set_under_passage(cell, orientation);
+    }
+
+    if let Some(start) = start {
+        maze.set_start(start);
+    }
+
+    if let Some(goal) = goal {
+        maze.set_goal(goal);
+    }
+
+    maze.mark_finished();
+
+    Ok(maze)
+}
+
+/// Whether a wall should be drawn between two adjacent cells,
+/// which is also the case on the boundary between a cell of the maze and one outside of it
+fn has_wall(maze: &Maze, cell1: MazePoint, cell2: MazePoint) -> bool {
+    match (maze.is_point_inside(cell1), maze.is_point_inside(cell2)) {
+        (true, true) => !maze.is_passage(cell1, cell2),
+        (true, false) | (false, true) => true,
+        (false, false) => false,
+    }
+}
+
+/// The two characters inside a cell: the start and goal markers, or the under-passage marker
+fn cell_interior(maze: &Maze, cell: MazePoint, style: TextStyle) -> [char; 2] {
+    if !maze.is_point_inside(cell) {
+        return [style.disabled_cell(); 2];
+    }
+
+    let marker = match maze.under_passage(cell) {
+        Some(Orientation::Horizontal) => style.over_passage(Orientation::Vertical),
+        Some(Orientation::Vertical) => style.over_passage(Orientation::Horizontal),
+        None => ' ',
+    };
+
+    [
+        if cell == maze.start() { 'S' } else { marker },
+        if cell == maze.goal() { 'G' } else { marker },
+    ]
+}
+
+#[derive(Debug)]
+pub enum ParseError {
+    Empty,
+    UnexpectedCharacter {
+        line: usize,
+        column: usize,
+        character: char,
+    },
+    MultipleStarts,
+    MultipleGoals,
+    InvalidUnderPassage(MazePoint),
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "Maze has no cells"),
+            Self::UnexpectedCharacter {
+                line,
+                column,
+                character,
+            } => write!(
+                f,
+                "Unexpected character '{}' inside a cell at line {}, column {}",
+                character, line, column
+            ),
+            Self::MultipleStarts => write!(f, "Maze has more than one start"),
+            Self::MultipleGoals => write!(f, "Maze has more than one goal"),
+            Self::InvalidUnderPassage(cell) => write!(
+                f,
+                "Cell ({}, {}) is marked as a crossing, but isn't open on all four sides",
+                cell.x, cell.y
+            ),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{self, Snapshot};
+
+    fn assert_round_trips(maze: &Maze) {
+        for &style in TextStyle::all() {
+            let text = render(maze, style);
+            let parsed = parse(&text).unwrap_or_else(|e| panic!("{}\n{}", e, text));
+
+            assert_eq!(Snapshot::of(&parsed), Snapshot::of(maze), "\n{}", text);
+            assert_eq!(render(&parsed, style), text);
+        }
+    }
+
+    #[test]
+    fn orthogonal_mazes_round_trip() {
+        for seed in 0..4 {
+            assert_round_trips(&testing::generated(7, 5, Topology::Orthogonal, seed));
+        }
+    }
+
+    #[test]
+    fn weave_mazes_round_trip() {
+        let mazes: Vec<Maze> = (0..4)
+            .map(|seed| testing::generated(12, 9, Topology::Weave, seed))
+            .collect();
+
+        assert!(mazes.iter().any(|maze| maze.under_passages().count() > 0));
+
+        for maze in &mazes {
+            assert_round_trips(maze);
+        }
+    }
+
+    #[test]
+    fn masked_mazes_round_trip() {
+        for &topology in Topology::all() {
+            assert_round_trips(&testing::masked(topology, 3));
+        }
+    }
+
+    #[test]
+    fn parses_text_written_by_hand() {
+        let text = "\
++--+--+--+--+
+|S    |     |
++--+  +--+  +
+|  |        |
++  +--+--+  +
+|          G|
++--+--+--+--+
+";
+        let maze = parse(text).unwrap();
+
+        assert_eq!((maze.width(), maze.height()), (4, 3));
+        assert!(maze.start() == MazePoint::new(0, 0));
+        assert!(maze.goal() == MazePoint::new(3, 2));
+        assert!(maze.is_passage(MazePoint::new(0, 0), MazePoint::new(1, 0)));
+        assert!(!maze.is_passage(MazePoint::new(1, 0), MazePoint::new(2, 0)));
+        assert_eq!(render(&maze, TextStyle::Ascii), text);
+
+        // The same maze, without trailing spaces or the closing newline
+        let trimmed: Vec<&str> = text.lines().map(str::trim_end).collect();
+        let maze = parse(&trimmed.join("\n")).unwrap();
+
+        assert_eq!(render(&maze, TextStyle::Ascii), text);
+    }
+
+    #[test]
+    fn empty_text_is_an_error() {
+        assert!(matches!(parse(""), Err(ParseError::Empty)));
+        assert!(matches!(parse("  \n\n"), Err(ParseError::Empty)));
+        assert!(matches!(parse("+--+\n"), Err(ParseError::Empty)));
+    }
+
+    #[test]
+    fn unexpected_characters_are_errors() {
+        let result = parse("+--+--+\n|  |x |\n+--+--+\n");
+
+        assert!(matches!(
+            result,
+            Err(ParseError::UnexpectedCharacter {
+                line: 2,
+                column: 5,
+                character: 'x',
+            })
+        ));
+    }
+
+    #[test]
+    fn several_starts_or_goals_are_errors() {
+        assert!(matches!(
+            parse("+--+--+\n|S  S |\n+--+--+\n"),
+            Err(ParseError::MultipleStarts)
+        ));
+        assert!(matches!(
+            parse("+--+--+\n|G  G |\n+--+--+\n"),
+            Err(ParseError::MultipleGoals)
+        ));
+    }
+
+    #[test]
+    fn crossings_must_be_open_on_all_sides() {
+        let closed = "\
++--+--+--+
+|        |
++  +  +  +
+|   ==   |
++  +--+  +
+|        |
++--+--+--+
+";
+        assert!(matches!(
+            parse(closed),
+            Err(ParseError::InvalidUnderPassage(cell)) if cell == MazePoint::new(1, 1)
+        ));
+
+        let open = closed.replace("+  +--+  +", "+  +  +  +");
+        let maze = parse(&open).unwrap();
+
+        assert!(maze.under_passage(MazePoint::new(1, 1)) == Some(Orientation::Vertical));
+    }
+}
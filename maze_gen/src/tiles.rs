@@ -0,0 +1,477 @@
+use serde::Serialize;
+
+use crate::maze::Maze;
+use crate::point::MazePoint;
+
+/// The size of a tile in pixels, which Tiled needs even though there's no tileset image
+const TILED_TILE_SIZE: u32 = 16;
+
+/// How many tiles wide corridors and walls are
+///
+/// One tile each gives the classic grid of twice the maze's size plus one.
+#[derive(Clone, Copy)]
+pub struct TileOptions {
+    pub corridor_width: u32,
+    pub wall_width: u32,
+}
+
+impl Default for TileOptions {
+    fn default() -> Self {
+        Self {
+            corridor_width: 1,
+            wall_width: 1,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Tile {
+    /// Outside the maze's mask
+    Empty,
+    Floor,
+    Wall,
+}
+
+impl Tile {
+    /// The tile's number in CSV, which is also its global ID in Tiled, where 0 is no tile
+    pub fn id(&self) -> u32 {
+        match self {
+            Tile::Empty => 0,
+            Tile::Floor => 1,
+            Tile::Wall => 2,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Tile::Empty => "empty",
+            Tile::Floor => "floor",
+            Tile::Wall => "wall",
+        }
+    }
+}
+
+/// A maze as a grid of floor and wall tiles, for game engines and level editors
+///
+/// Cells are squares of corridor tiles, with wall tiles between them unless there's a passage.
+/// Under-passages can't be shown on a single layer, so the crossings of a weave maze
+/// look like junctions.
+pub struct TileMap {
+    width: u32,
+    height: u32,
+    tiles: Vec<Tile>,
+    options: TileOptions,
+    start: MazePoint,
+    goal: MazePoint,
+}
+
+impl TileMap {
+    pub fn new(maze: &Maze, options: TileOptions) -> Self {
+        let TileOptions {
+            corridor_width,
+            wall_width,
+        } = options;
+        let cell_width = corridor_width + wall_width;
+
+        let width = maze.width() as u32 * cell_width + wall_width;
+        let height = maze.height() as u32 * cell_width + wall_width;
+
+        // Every tile falls on either a cell, a wall between two cells, or a corner between four,
+        // as `(cell, is_on_wall)` for both axes, with the cell after the wall for walls and corners
+        let axis = |position: u32| {
+            let offset = position % cell_width;
+            let cell = (position / cell_width) as i64;
+
+            (cell, offset < wall_width)
+        };
+
+        let tiles = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                let (cell_x, is_on_wall_x) = axis(x);
+                let (cell_y, is_on_wall_y) = axis(y);
+                let cell = MazePoint::new(cell_x, cell_y);
+
+                let neighbours: &[MazePoint] = match (is_on_wall_x, is_on_wall_y) {
+                    (false, false) => &[cell],
+                    (true, false) => &[cell + (-1, 0), cell],
+                    (false, true) => &[cell + (0, -1), cell],
+                    (true, true) => &[cell + (-1, -1), cell + (0, -1), cell + (-1, 0), cell],
+                };
+
+                if !neighbours.iter().any(|&cell| maze.is_point_inside(cell)) {
+                    Tile::Empty
+                } else if neighbours.len() == 1
+                    || (neighbours.len() == 2 && maze.is_passage(neighbours[0], neighbours[1]))
+                {
+                    Tile::Floor
+                } else {
+                    Tile::Wall
+                }
+            })
+            .collect();
+
+        Self {
+            width,
+            height,
+            tiles,
+            options,
+            start: maze.start(),
+            goal: maze.goal(),
+        }
+    }
+
+    /// In tiles
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    /// In tiles
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn tile(&self, x: u32, y: u32) -> Tile {
+        self.tiles[(y * self.width + x) as usize]
+    }
+
+    /// One line per row of tile IDs, separated by commas
+    pub fn to_csv(&self) -> String {
+        self.rows().map(|row| row.join(",") + "\n").collect()
+    }
+
+    /// A Tiled map with a tile layer for the maze and an object layer marking the start and goal
+    ///
+    /// The tileset has no image, only a type for every tile, so that it can be swapped for a real one.
+    pub fn to_tmx(&self) -> String {
+        let mut tmx = format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+             <map version=\"1.10\" orientation=\"orthogonal\" renderorder=\"right-down\" \
+             width=\"{width}\" height=\"{height}\" tilewidth=\"{size}\" tileheight=\"{size}\" \
+             infinite=\"0\" nextlayerid=\"3\" nextobjectid=\"3\">\n\
+             <tileset firstgid=\"1\" name=\"maze\" tilewidth=\"{size}\" tileheight=\"{size}\" \
+             tilecount=\"2\" columns=\"0\">\n",
+            width = self.width,
+            height = self.height,
+            size = TILED_TILE_SIZE,
+        );
+
+        for tile in [Tile::Floor, Tile::Wall] {
+            tmx += &format!(
+                "<tile id=\"{}\" type=\"{}\"/>\n",
+                tile.id() - 1,
+                tile.name()
+            );
+        }
+
+        tmx += &format!(
+            "</tileset>\n\
+             <layer id=\"1\" name=\"maze\" width=\"{}\" height=\"{}\">\n\
+             <data encoding=\"csv\">\n{}\n</data>\n\
+             </layer>\n\
+             <objectgroup id=\"2\" name=\"markers\">\n",
+            self.width,
+            self.height,
+            self.rows()
+                .map(|row| row.join(","))
+                .collect::<Vec<_>>()
+                .join(",\n"),
+        );
+
+        for (id, (name, x, y)) in self.markers().into_iter().enumerate() {
+            tmx += &format!(
+                "<object id=\"{}\" name=\"{}\" x=\"{}\" y=\"{}\"><point/></object>\n",
+                id + 1,
+                name,
+                x,
+                y
+            );
+        }
+
+        tmx += "</objectgroup>\n</map>\n";
+
+        tmx
+    }
+
+    /// The same map as [`TileMap::to_tmx`], in Tiled's JSON format
+    pub fn to_tiled_json(&self) -> String {
+        let layer = |id, name, data: Option<Vec<u32>>, objects| TiledLayer {
+            id,
+            name,
+            layer_type: if data.is_some() {
+                "tilelayer"
+            } else {
+                "objectgroup"
+            },
+            width: data.is_some().then_some(self.width),
+            height: data.is_some().then_some(self.height),
+            data,
+            objects,
+            x: 0,
+            y: 0,
+            opacity: 1.0,
+            visible: true,
+        };
+
+        let objects = self
+            .markers()
+            .into_iter()
+            .enumerate()
+            .map(|(id, (name, x, y))| TiledObject {
+                id: id as u32 + 1,
+                name,
+                x,
+                y,
+                point: true,
+            })
+            .collect();
+
+        let map = TiledMap {
+            map_type: "map",
+            version: "1.10",
+            orientation: "orthogonal",
+            renderorder: "right-down",
+            width: self.width,
+            height: self.height,
+            tilewidth: TILED_TILE_SIZE,
+            tileheight: TILED_TILE_SIZE,
+            infinite: false,
+            nextlayerid: 3,
+            nextobjectid: 3,
+            layers: vec![
+                layer(
+                    1,
+                    "maze",
+                    Some(self.tiles.iter().map(Tile::id).collect()),
+                    None,
+                ),
+                layer(2, "markers", None, Some(objects)),
+            ],
+            tilesets: vec![TiledTileset {
+                firstgid: 1,
+                name: "maze",
+                tilewidth: TILED_TILE_SIZE,
+                tileheight: TILED_TILE_SIZE,
+                tilecount: 2,
+                columns: 0,
+                tiles: [Tile::Floor, Tile::Wall]
+                    .into_iter()
+                    .map(|tile| TiledTile {
+                        id: tile.id() - 1,
+                        tile_type: tile.name(),
+                    })
+                    .collect(),
+            }],
+        };
+
+        // Serializing plain data to a string can't fail
+        serde_json::to_string(&map).unwrap() + "\n"
+    }
+
+    fn rows(&self) -> impl Iterator<Item = Vec<String>> + '_ {
+        self.tiles
+            .chunks(self.width as usize)
+            .map(|row| row.iter().map(|tile| tile.id().to_string()).collect())
+    }
+
+    /// The start and goal, at the center of their cells in pixels
+    fn markers(&self) -> [(&'static str, f64, f64); 2] {
+        let TileOptions {
+            corridor_width,
+            wall_width,
+        } = self.options;
+
+        let center = |position: i64| {
+            let tiles = position as f64 * (corridor_width + wall_width) as f64
+                + wall_width as f64
+                + corridor_width as f64 / 2.0;
+
+            tiles * TILED_TILE_SIZE as f64
+        };
+
+        [
+            ("start", center(self.start.x), center(self.start.y)),
+            ("goal", center(self.goal.x), center(self.goal.y)),
+        ]
+    }
+}
+
+#[derive(Serialize)]
+struct TiledMap {
+    #[serde(rename = "type")]
+    map_type: &'static str,
+    version: &'static str,
+    orientation: &'static str,
+    renderorder: &'static str,
+    width: u32,
+    height: u32,
+    tilewidth: u32,
+    tileheight: u32,
+    infinite: bool,
+    nextlayerid: u32,
+    nextobjectid: u32,
+    layers: Vec<TiledLayer>,
+    tilesets: Vec<TiledTileset>,
+}
+
+#[derive(Serialize)]
+struct TiledLayer {
+    id: u32,
+    name: &'static str,
+    #[serde(rename = "type")]
+    layer_type: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    width: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    height: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<Vec<u32>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    objects: Option<Vec<TiledObject>>,
+    x: i32,
+    y: i32,
+    opacity: f64,
+    visible: bool,
+}
+
+#[derive(Serialize)]
+struct TiledObject {
+    id: u32,
+    name: &'static str,
+    x: f64,
+    y: f64,
+    point: bool,
+}
+
+#[derive(Serialize)]
+struct TiledTileset {
+    firstgid: u32,
+    name: &'static str,
+    tilewidth: u32,
+    tileheight: u32,
+    tilecount: u32,
+    columns: u32,
+    tiles: Vec<TiledTile>,
+}
+
+#[derive(Serialize)]
+struct TiledTile {
+    id: u32,
+    #[serde(rename = "type")]
+    tile_type: &'static str,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::maze::Topology;
+    use crate::testing;
+    use crate::text;
+
+    const MAZE: &str = "\
++--+--+--+
+|S    |  |
++--+  +  +
+|       G|
++--+--+--+
+";
+
+    #[test]
+    fn writes_one_tile_per_cell_and_wall() {
+        let maze = text::parse(MAZE).unwrap();
+        let map = TileMap::new(&maze, TileOptions::default());
+
+        assert_eq!((map.width(), map.height()), (7, 5));
+        assert_eq!(
+            map.to_csv(),
+            "\
+2,2,2,2,2,2,2
+2,1,1,1,2,1,2
+2,2,2,1,2,1,2
+2,1,1,1,1,1,2
+2,2,2,2,2,2,2
+"
+        );
+    }
+
+    #[test]
+    fn wider_corridors_and_walls_repeat_the_same_tiles() {
+        for topology in [Topology::Orthogonal, Topology::Weave] {
+            let maze = testing::generated(9, 6, topology, 3);
+            let thin = TileMap::new(&maze, TileOptions::default());
+
+            for (corridor_width, wall_width) in [(1, 2), (2, 1), (3, 2), (4, 4)] {
+                let options = TileOptions {
+                    corridor_width,
+                    wall_width,
+                };
+                let wide = TileMap::new(&maze, options);
+                let cell_width = corridor_width + wall_width;
+
+                assert_eq!(wide.width(), 9 * cell_width + wall_width);
+                assert_eq!(wide.height(), 6 * cell_width + wall_width);
+
+                // Where a tile of the wide map falls on the map with one tile per cell and wall
+                let thin_position = |position: u32| {
+                    2 * (position / cell_width) + (position % cell_width >= wall_width) as u32
+                };
+
+                for y in 0..wide.height() {
+                    for x in 0..wide.width() {
+                        assert!(
+                            wide.tile(x, y) == thin.tile(thin_position(x), thin_position(y)),
+                            "tile ({}, {}) with {}x{} tiles",
+                            x,
+                            y,
+                            corridor_width,
+                            wall_width
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn cells_outside_the_mask_are_empty() {
+        let maze = testing::masked(Topology::Orthogonal, 0);
+        let map = TileMap::new(&maze, TileOptions::default());
+
+        for y in 0..map.height() {
+            for x in 0..map.width() {
+                let neighbours = [x.saturating_sub(1) / 2, x / 2]
+                    .into_iter()
+                    .flat_map(|cell_x| {
+                        [y.saturating_sub(1) / 2, y / 2]
+                            .map(|cell_y| MazePoint::new(cell_x as i64, cell_y as i64))
+                    });
+                let is_near_maze = neighbours
+                    .into_iter()
+                    .any(|cell| maze.is_point_inside(cell));
+
+                assert_eq!(map.tile(x, y) == Tile::Empty, !is_near_maze);
+            }
+        }
+    }
+
+    #[test]
+    fn markers_are_at_the_centers_of_the_start_and_goal() {
+        let maze = text::parse(MAZE).unwrap();
+        let map = TileMap::new(
+            &maze,
+            TileOptions {
+                corridor_width: 3,
+                wall_width: 2,
+            },
+        );
+        let size = TILED_TILE_SIZE as f64;
+
+        assert_eq!(
+            map.markers(),
+            [
+                ("start", 3.5 * size, 3.5 * size),
+                ("goal", 13.5 * size, 8.5 * size),
+            ]
+        );
+    }
+}
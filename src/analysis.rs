@@ -0,0 +1,48 @@
+use std::collections::HashMap;
+
+use maze_gen::maze::Maze;
+use maze_gen::point::MazePoint;
+use maze_gen::solve::{self, Solver};
+
+/// The distances and solution of a maze, which the tooltip and the editor show every frame
+/// but only need working out again once the maze or the solver changes
+pub struct Analysis {
+    revision: u64,
+    solver: Solver,
+    /// From the start, for every cell that can be reached
+    distances: HashMap<MazePoint, usize>,
+    solution: Option<Vec<MazePoint>>,
+}
+
+impl Analysis {
+    pub fn new(maze: &Maze, solver: Solver) -> Self {
+        Self {
+            revision: maze.revision(),
+            solver,
+            distances: solve::distances(maze, maze.start()),
+            solution: solve::solve(maze, solver),
+        }
+    }
+
+    /// The analysis in `cache`, worked out again first if it's missing or out of date
+    pub fn cached<'a>(cache: &'a mut Option<Self>, maze: &Maze, solver: Solver) -> &'a Self {
+        let is_current = cache.as_ref().is_some_and(|analysis| {
+            analysis.revision == maze.revision() && analysis.solver == solver
+        });
+
+        if !is_current {
+            *cache = None;
+        }
+
+        cache.get_or_insert_with(|| Self::new(maze, solver))
+    }
+
+    pub fn distances(&self) -> &HashMap<MazePoint, usize> {
+        &self.distances
+    }
+
+    /// The path from the start to the goal, if the goal can be reached
+    pub fn solution(&self) -> Option<&[MazePoint]> {
+        self.solution.as_deref()
+    }
+}
@@ -1,67 +1,142 @@
-use rand::rngs::ThreadRng;
+use std::fs;
+use std::path::PathBuf;
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
 
 use sdl2::event::{Event, WindowEvent};
-use sdl2::keyboard::Scancode;
+use sdl2::keyboard::{Mod, Scancode};
 use sdl2::mouse::MouseButton;
 use sdl2::render::WindowCanvas;
 use sdl2::ttf::Sdl2TtfContext;
+use sdl2::video::FullscreenType;
+
+use maze_gen::mask::{Mask, MaskError};
+use maze_gen::maze::{Algorithm, Maze, Topology};
+use maze_gen::point::MazePoint;
+use maze_gen::save;
+use maze_gen::share::{self, ShareError};
+use maze_gen::solve::Solver;
 
+use crate::analysis::Analysis;
+use crate::clipboard::Clipboard;
 use crate::context::Context;
+use crate::editor::{self, EditHistory};
+use crate::export;
 use crate::graphics::{FontLoadError, Graphics};
-use crate::layout::Layout;
-use crate::maze::Maze;
-use crate::point::WindowPoint;
-use crate::stable_loop::StableLoop;
-use crate::ui::{ButtonId, Ui};
-use crate::TARGET_FPS;
+use crate::hud::{self, GenerationStats};
+use crate::inspect;
+use crate::keymap::{Action, KeyMap};
+use crate::layout::{Layout, WindowLayout};
+use crate::overlays::Overlays;
+use crate::speed::Speed;
+use crate::stable_loop::{StableLoop, Time};
+use crate::theme::Theme;
+use crate::ui::{ButtonId, CheckboxId, DropdownId, SliderId, SpinnerId, TextFieldId, Ui, UiEvent};
+use crate::window_point::WindowPoint;
+use crate::{
+    GENERATION_SPEED, MAX_SPEED_BATCH_SIZE, MAX_SPEED_FRAME_BUDGET, MAZE_MAX_SIZE, MAZE_MIN_SIZE,
+    TARGET_FPS,
+};
 
 pub struct App<'ttf> {
     graphics: Graphics<'ttf>,
     layout: Layout,
-    rng: ThreadRng,
+    view_settings: ViewSettings,
+    rng: ChaCha8Rng,
+    clipboard: Clipboard,
+    key_map: KeyMap,
+    /// Whether the key bindings are shown over the maze
+    is_showing_help: bool,
+    /// Whether the HUD is shown over the maze
+    is_showing_hud: bool,
+    /// Whether clicking the maze edits it
+    is_editing: bool,
+    edit_history: EditHistory,
+    /// What the tooltip and the editor show about the maze, kept until it changes
+    analysis: Option<Analysis>,
+    /// Whether the maze takes generation steps by itself every update
+    is_auto_stepping: bool,
+    /// Fractional steps carried over between updates, for speeds below one step per update
+    step_accumulator: f64,
+    generation_stats: GenerationStats,
 
     window_width: u32,
     window_height: u32,
+    /// Where the mouse is, while it's in the window
+    mouse: Option<WindowPoint>,
 
+    maze_settings: MazeSettings,
+    /// Changes from the settings panel that take effect once the maze is reset
+    pending_maze_settings: Option<MazeSettings>,
     maze: Maze,
     ui: Ui,
+    is_panel_open: bool,
 }
 
 impl<'ttf> App<'ttf> {
     pub fn new(
-        maze_width: i64,
-        maze_height: i64,
+        maze_settings: MazeSettings,
+        maze: Maze,
+        rng: ChaCha8Rng,
+        mut view_settings: ViewSettings,
         layout: Layout,
         ttf_ctx: &'ttf Sdl2TtfContext,
         canvas: &WindowCanvas,
     ) -> Result<Self, FontLoadError> {
-        let graphics = Graphics::new(ttf_ctx, canvas)?;
-        let rng = rand::thread_rng();
+        let graphics = Graphics::new(ttf_ctx, canvas, view_settings.theme)?;
+
+        let clipboard = Clipboard::new(canvas);
 
         let (window_width, window_height) = canvas.window().size();
 
-        let maze = Maze::new(maze_width, maze_height);
         let ui = Ui::new();
 
+        // A speed of 0 starts the generation paused, at the default speed once it's resumed
+        let is_auto_stepping = view_settings.speed != Speed::StepsPerUpdate(0.0);
+        if !is_auto_stepping {
+            view_settings.speed = GENERATION_SPEED;
+        }
+
         Ok(Self {
             graphics,
             layout,
+            view_settings,
             rng,
+            clipboard,
+            key_map: KeyMap::default(),
+            is_showing_help: false,
+            is_showing_hud: false,
+            is_editing: false,
+            edit_history: EditHistory::default(),
+            analysis: None,
+            is_auto_stepping,
+            step_accumulator: 0.0,
+            generation_stats: GenerationStats::default(),
             window_width,
             window_height,
+            mouse: None,
+            maze_settings,
+            pending_maze_settings: None,
             maze,
             ui,
+            is_panel_open: false,
         })
     }
 
-    fn handle_events(&mut self, events: impl Iterator<Item = Event>) -> bool {
+    /// Replaces the default key bindings
+    pub fn with_key_map(mut self, key_map: KeyMap) -> Self {
+        self.key_map = key_map;
+        self
+    }
+
+    fn handle_events(&mut self, ctx: &mut Context) -> bool {
+        // Some actions need the window, which can't be borrowed while polling events
+        let events: Vec<Event> = ctx.event_pump().poll_iter().collect();
+
         for event in events {
             match event {
-                Event::Quit { .. }
-                | Event::KeyUp {
-                    scancode: Some(Scancode::Escape),
-                    ..
-                } => {
+                Event::Quit { .. } => {
                     return false;
                 }
 
@@ -73,43 +148,702 @@ impl<'ttf> App<'ttf> {
                     self.window_height = window_height as u32;
                 }
 
-                // Event::MouseMotion { x, y, .. } => {
-                //     self.ui.on_mouse_move(WindowPoint::new(x as _, y as _));
-                // }
-
-                // Event::MouseButtonDown {
-                //     mouse_btn: MouseButton::Left,
-                //     x,
-                //     y,
-                //     ..
-                // } => {
-                //     self.ui.on_mouse_press(WindowPoint::new(x as _, y as _));
-                // }
-
-                // Event::MouseButtonUp {
-                //     mouse_btn: MouseButton::Left,
-                //     x,
-                //     y,
-                //     ..
-                // } => {
-                //     if let Some(clicked_button) =
-                //         self.ui.on_mouse_release(WindowPoint::new(x as _, y as _))
-                //     {
-                //         match clicked_button {
-                //             ButtonId::Step => {
-                //                 self.maze.step(&mut self.rng);
-                //             }
-
-                //             _ => {}
-                //         }
-                //     }
-                // }
+                Event::Window {
+                    win_event: WindowEvent::Leave,
+                    ..
+                } => self.mouse = None,
+
+                // While typing in a text field, keys edit it instead of doing their actions
+                Event::KeyDown {
+                    scancode: Some(scancode),
+                    keymod,
+                    ..
+                } if self.ui.focused_text_field().is_some() => {
+                    self.on_typing_key(scancode, keymod);
+                }
+
+                Event::TextInput { text, .. } => self.ui.on_text_input(&text),
+
+                Event::KeyDown {
+                    scancode: Some(scancode),
+                    repeat,
+                    ..
+                } => {
+                    let Some(action) = self.key_map.action(scancode) else {
+                        continue;
+                    };
+
+                    if (!repeat || action.is_repeatable()) && !self.on_action(action, ctx.canvas())
+                    {
+                        return false;
+                    }
+                }
+
+                Event::MouseMotion { x, y, .. } => {
+                    let mouse = WindowPoint::new(x as _, y as _);
+                    self.mouse = Some(mouse);
+
+                    if self.edit_history.is_painting() {
+                        if let Some(wall) = self.wall_at(mouse) {
+                            self.edit_history.paint(&mut self.maze, wall);
+                        }
+                    }
+
+                    if let Some(ui_event) = self.ui.on_mouse_move(mouse) {
+                        self.on_ui_event(ui_event);
+                    }
+                }
+
+                Event::MouseButtonDown {
+                    mouse_btn: MouseButton::Left,
+                    x,
+                    y,
+                    ..
+                } => {
+                    let mouse = WindowPoint::new(x as _, y as _);
+                    let is_on_ui = self.ui.is_over_widget(mouse) || self.ui.is_dropdown_open();
+
+                    if let Some(ui_event) = self.ui.on_mouse_press(mouse) {
+                        self.on_ui_event(ui_event);
+                    }
+
+                    if self.is_editing && !is_on_ui {
+                        if let Some(wall) = self.wall_at(mouse) {
+                            self.edit_history.start_stroke(&mut self.maze, wall);
+                        }
+                    }
+                }
+
+                // Right-clicking a cell while editing moves the start there, or the goal with Shift
+                Event::MouseButtonDown {
+                    mouse_btn: MouseButton::Right,
+                    x,
+                    y,
+                    ..
+                } if self.is_editing => {
+                    let mouse = WindowPoint::new(x as _, y as _);
+
+                    if self.ui.is_over_widget(mouse) {
+                        continue;
+                    }
+
+                    let Some(cell) = self.window_layout().maze.cell_at(mouse) else {
+                        continue;
+                    };
+
+                    let keyboard_state = ctx.event_pump().keyboard_state();
+                    let is_shift = keyboard_state.is_scancode_pressed(Scancode::LShift)
+                        || keyboard_state.is_scancode_pressed(Scancode::RShift);
+
+                    if is_shift {
+                        self.edit_history.set_goal(&mut self.maze, cell);
+                    } else {
+                        self.edit_history.set_start(&mut self.maze, cell);
+                    }
+                }
+
+                Event::MouseButtonUp {
+                    mouse_btn: MouseButton::Left,
+                    x,
+                    y,
+                    ..
+                } => {
+                    self.edit_history.end_stroke();
+
+                    if let Some(ui_event) =
+                        self.ui.on_mouse_release(WindowPoint::new(x as _, y as _))
+                    {
+                        self.on_ui_event(ui_event);
+                    }
+                }
+
                 _ => {}
             }
         }
 
         true
     }
+
+    /// Does what a key is bound to, returning whether to keep going
+    ///
+    /// Keys that change the maze's settings regenerate it right away.
+    fn on_action(&mut self, action: Action, canvas: &mut WindowCanvas) -> bool {
+        let MazeSettings { width, height, .. } = *self.next_maze_settings();
+
+        match action {
+            Action::Quit => return false,
+
+            Action::Step => self.step(),
+            Action::StepBack => self.step_back(),
+            Action::AutoStepOrPause => self.toggle_auto_stepping(),
+            Action::Finish => self.finish(),
+            Action::Reset => self.reset_maze(),
+
+            Action::ToggleSolution => {
+                self.view_settings.overlays.solution = !self.view_settings.overlays.solution;
+            }
+            Action::ToggleHeatmap => {
+                self.view_settings.overlays.heatmap = !self.view_settings.overlays.heatmap;
+            }
+
+            Action::CycleAlgorithm => {
+                let algorithms = Algorithm::all();
+                let algorithm = algorithms[(self.algorithm_index() + 1) % algorithms.len()];
+
+                self.change_maze_settings(true, |settings| settings.algorithm = algorithm);
+            }
+
+            Action::Wider => self.resize_maze(true, width + 1, height),
+            Action::Narrower => self.resize_maze(true, width - 1, height),
+            Action::Taller => self.resize_maze(true, width, height + 1),
+            Action::Shorter => self.resize_maze(true, width, height - 1),
+
+            Action::KeepSquare => {
+                let keep_square = !self.next_maze_settings().keep_square;
+
+                self.set_keep_square(true, keep_square);
+            }
+
+            Action::Faster => self.change_speed(1),
+            Action::Slower => self.change_speed(-1),
+
+            Action::ExportPng => self.export_png(),
+            Action::SaveMaze => self.save_maze(),
+            Action::CopyCode => self.copy_code(),
+            Action::PasteCode => self.paste_code(),
+
+            Action::ToggleFullscreen => Self::toggle_fullscreen(canvas),
+            Action::ToggleSettings => self.is_panel_open = !self.is_panel_open,
+            Action::ToggleEditing => self.toggle_editing(),
+            Action::Undo => self.edit_history.undo(&mut self.maze),
+            Action::Redo => self.edit_history.redo(&mut self.maze),
+            Action::ToggleHud => self.is_showing_hud = !self.is_showing_hud,
+            Action::ToggleHelp => self.is_showing_help = !self.is_showing_help,
+        }
+
+        true
+    }
+
+    /// Edits the focused text field, with the clipboard shortcuts going through the app's clipboard
+    fn on_typing_key(&mut self, scancode: Scancode, keymod: Mod) {
+        let is_ctrl =
+            keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD | Mod::LGUIMOD | Mod::RGUIMOD);
+
+        match scancode {
+            Scancode::C | Scancode::X if is_ctrl => {
+                let Some(selected_text) = self.ui.selected_text() else {
+                    return;
+                };
+
+                if let Err(e) = self.clipboard.set_text(&selected_text) {
+                    eprintln!("Failed to copy text: {}", e);
+                    return;
+                }
+
+                // Typing nothing replaces the selection with nothing
+                if scancode == Scancode::X {
+                    self.ui.on_text_input("");
+                }
+            }
+
+            Scancode::V if is_ctrl => match self.clipboard.text() {
+                Ok(text) => self.ui.on_text_input(&text),
+                Err(e) => eprintln!("Failed to paste text: {}", e),
+            },
+
+            _ => {
+                if let Some(ui_event) = self.ui.on_key_down(scancode, keymod) {
+                    self.on_ui_event(ui_event);
+                }
+            }
+        }
+    }
+
+    /// Changes to the maze's settings from the settings panel wait for a reset,
+    /// while the way it's shown changes right away
+    fn on_ui_event(&mut self, ui_event: UiEvent) {
+        match ui_event {
+            UiEvent::ButtonClicked(button_id) => self.on_button_click(button_id),
+
+            UiEvent::SliderChanged(SliderId::Speed, level) => {
+                self.view_settings.speed = Speed::levels()[level];
+            }
+            UiEvent::SliderChanged(SliderId::Timeline, step) => {
+                if !self.is_editing {
+                    self.is_auto_stepping = false;
+                    self.maze.go_to_step(step);
+                }
+            }
+
+            UiEvent::DropdownChanged(DropdownId::Algorithm, index) => {
+                self.change_maze_settings(false, |settings| {
+                    settings.algorithm = Algorithm::all()[index];
+                });
+            }
+            UiEvent::DropdownChanged(DropdownId::Topology, index) => {
+                self.change_maze_settings(false, |settings| {
+                    settings.topology = Topology::all()[index];
+                });
+            }
+
+            UiEvent::DropdownChanged(DropdownId::Solver, index) => {
+                self.view_settings.overlays.solver = Solver::all()[index];
+            }
+            UiEvent::DropdownChanged(DropdownId::Theme, index) => {
+                let (_, theme) = Theme::all()[index];
+
+                self.view_settings.theme = theme;
+                self.graphics.set_theme(theme);
+            }
+
+            UiEvent::SpinnerChanged(SpinnerId::Width, width) => {
+                let height = self.next_maze_settings().height;
+
+                self.resize_maze(false, width, height);
+            }
+            UiEvent::SpinnerChanged(SpinnerId::Height, height) => {
+                let width = self.next_maze_settings().width;
+
+                self.resize_maze(false, width, height);
+            }
+
+            UiEvent::CheckboxChanged(CheckboxId::KeepSquare, keep_square) => {
+                self.set_keep_square(false, keep_square);
+            }
+            UiEvent::CheckboxChanged(CheckboxId::Solution, solution) => {
+                self.view_settings.overlays.solution = solution;
+            }
+            UiEvent::CheckboxChanged(CheckboxId::Heatmap, heatmap) => {
+                self.view_settings.overlays.heatmap = heatmap;
+            }
+
+            UiEvent::TextSubmitted(TextFieldId::Seed) => {
+                match self.ui.text_field_text(TextFieldId::Seed).parse() {
+                    Ok(seed) => {
+                        self.change_maze_settings(false, |settings| settings.seed = seed);
+                        self.ui.stop_typing();
+                    }
+
+                    Err(_) => self.ui.set_text_field_invalid(TextFieldId::Seed),
+                }
+            }
+            UiEvent::TextSubmitted(TextFieldId::ShareCode) => {
+                let code = self.ui.text_field_text(TextFieldId::ShareCode).to_string();
+
+                match self.load_code(&code) {
+                    Ok(()) => {
+                        self.ui.stop_typing();
+                        self.ui.set_text_field(TextFieldId::ShareCode, "");
+                    }
+
+                    Err(e) => {
+                        eprintln!("{}", e);
+                        self.ui.set_text_field_invalid(TextFieldId::ShareCode);
+                    }
+                }
+            }
+        }
+    }
+
+    fn on_button_click(&mut self, button_id: ButtonId) {
+        match button_id {
+            ButtonId::Step => self.step(),
+            ButtonId::StepBack => self.step_back(),
+            ButtonId::AutoStepOrPause => self.toggle_auto_stepping(),
+            ButtonId::Finish => self.finish(),
+            ButtonId::Reset => self.reset_maze(),
+            ButtonId::Edit => self.toggle_editing(),
+            ButtonId::Settings => self.is_panel_open = !self.is_panel_open,
+
+            ButtonId::NewSeed => {
+                self.change_maze_settings(false, |settings| settings.seed = rand::random());
+            }
+        }
+    }
+
+    /// The settings the maze will have once it's reset
+    fn next_maze_settings(&self) -> &MazeSettings {
+        self.pending_maze_settings
+            .as_ref()
+            .unwrap_or(&self.maze_settings)
+    }
+
+    /// Changes the settings the maze will have once it's reset,
+    /// and resets it right away if `regenerate` is set
+    fn change_maze_settings(&mut self, regenerate: bool, change: impl FnOnce(&mut MazeSettings)) {
+        let mut maze_settings = self.next_maze_settings().clone();
+        change(&mut maze_settings);
+
+        self.pending_maze_settings = Some(maze_settings);
+
+        if regenerate {
+            self.reset_maze();
+        }
+    }
+
+    fn algorithm_index(&self) -> usize {
+        Algorithm::all()
+            .iter()
+            .position(|&algorithm| algorithm == self.next_maze_settings().algorithm)
+            .unwrap_or(0)
+    }
+
+    /// Keeping the maze square makes it as tall as it is wide
+    fn set_keep_square(&mut self, regenerate: bool, keep_square: bool) {
+        self.change_maze_settings(regenerate, |settings| {
+            settings.keep_square = keep_square;
+
+            if keep_square {
+                *settings = settings.resized(settings.width, settings.width);
+            }
+        });
+    }
+
+    fn toggle_fullscreen(canvas: &mut WindowCanvas) {
+        let window = canvas.window_mut();
+
+        let fullscreen_type = match window.fullscreen_state() {
+            FullscreenType::Off => FullscreenType::Desktop,
+            FullscreenType::Desktop | FullscreenType::True => FullscreenType::Off,
+        };
+
+        if let Err(e) = window.set_fullscreen(fullscreen_type) {
+            eprintln!("Failed to toggle fullscreen: {}", e);
+        }
+    }
+
+    /// Only finished mazes can be edited, so starting to edit finishes the maze
+    fn toggle_editing(&mut self) {
+        self.is_editing = !self.is_editing;
+        self.edit_history.end_stroke();
+
+        if self.is_editing {
+            self.finish();
+            self.is_auto_stepping = false;
+        }
+    }
+
+    /// The wall that a click in the editor would toggle
+    fn wall_at(&self, mouse: WindowPoint) -> Option<(MazePoint, MazePoint)> {
+        editor::wall_at(&self.maze, &self.window_layout().maze, mouse)
+    }
+
+    /// Goes up or down a number of speed levels
+    fn change_speed(&mut self, levels: isize) {
+        self.view_settings.speed = self.view_settings.speed.changed_by(levels);
+    }
+
+    /// Takes a generation step, unless the maze is being edited
+    ///
+    /// The editor's undo history is of changes to the finished maze, so generation
+    /// can't go on, or go back, until editing stops.
+    fn step(&mut self) {
+        if !self.is_editing {
+            self.time_generation(|app| app.maze.step(&mut app.rng));
+        }
+    }
+
+    fn finish(&mut self) {
+        self.time_generation(|app| app.maze.finish(&mut app.rng));
+    }
+
+    /// Generates some of the maze, adding the time it takes to the generation time in the HUD
+    fn time_generation(&mut self, generate: impl FnOnce(&mut Self)) {
+        let time = Time::new();
+        generate(self);
+        self.generation_stats.elapsed += time.time_since_start();
+    }
+
+    /// Undoes a generation step, pausing so it isn't taken again right away
+    fn step_back(&mut self) {
+        if !self.is_editing {
+            self.is_auto_stepping = false;
+            self.maze.step_back();
+        }
+    }
+
+    fn toggle_auto_stepping(&mut self) {
+        if !self.is_editing {
+            self.is_auto_stepping = !self.is_auto_stepping;
+        }
+    }
+
+    /// Takes as many generation steps as the speed allows in a single update
+    fn auto_step(&mut self) {
+        match self.view_settings.speed {
+            Speed::StepsPerUpdate(steps) => {
+                self.step_accumulator += steps;
+
+                while self.step_accumulator >= 1.0 {
+                    self.maze.step(&mut self.rng);
+                    self.step_accumulator -= 1.0;
+                }
+            }
+
+            // Checking the time after every step would take longer than the steps themselves
+            Speed::Max => {
+                let time = Time::new();
+                let budget = MAX_SPEED_FRAME_BUDGET / TARGET_FPS;
+
+                while !self.maze.is_finished() && time.time_since_start().as_secs_f64() < budget {
+                    for _ in 0..MAX_SPEED_BATCH_SIZE {
+                        self.maze.step(&mut self.rng);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Starts generating the maze over, with any changes from the settings panel
+    ///
+    /// Shrinking a mask may leave it without any enabled cells, in which case the maze is kept
+    /// and so are the changes, so they can be fixed.
+    fn reset_maze(&mut self) {
+        let maze_settings = self.next_maze_settings().clone();
+
+        match maze_settings.build_maze() {
+            Ok(maze) => {
+                self.replace_maze(maze);
+                self.rng = maze_settings.build_rng();
+                self.maze_settings = maze_settings;
+                self.pending_maze_settings = None;
+            }
+
+            Err(e) => eprintln!(
+                "Failed to create a {}x{} maze: {}",
+                maze_settings.width, maze_settings.height, e
+            ),
+        }
+    }
+
+    /// Switches to a different maze, stopping editing and dropping the edits made to the old one,
+    /// which can't be undone or redone on the new one
+    fn replace_maze(&mut self, maze: Maze) {
+        self.maze = maze;
+        self.generation_stats = GenerationStats::default();
+        self.is_editing = false;
+        self.edit_history = EditHistory::default();
+    }
+
+    /// Shows the current settings in the widgets, however they were changed
+    fn sync_ui(&mut self) {
+        let ViewSettings {
+            speed,
+            theme,
+            overlays,
+        } = self.view_settings;
+        let MazeSettings {
+            width,
+            height,
+            keep_square,
+            topology,
+            seed,
+            ..
+        } = *self.next_maze_settings();
+
+        self.ui
+            .set_toggled(ButtonId::AutoStepOrPause, self.is_auto_stepping);
+        self.ui
+            .set_toggled(ButtonId::Reset, self.pending_maze_settings.is_some());
+        self.ui.set_toggled(ButtonId::Edit, self.is_editing);
+
+        for button_id in [
+            ButtonId::Step,
+            ButtonId::StepBack,
+            ButtonId::AutoStepOrPause,
+        ] {
+            self.ui.set_button_enabled(button_id, !self.is_editing);
+        }
+        self.ui
+            .set_slider_enabled(SliderId::Timeline, !self.is_editing);
+
+        self.ui.set_toggled(ButtonId::Settings, self.is_panel_open);
+        self.ui.set_text_field(TextFieldId::Seed, &seed.to_string());
+
+        self.ui.set_slider(
+            SliderId::Speed,
+            speed.level(),
+            Speed::levels().len(),
+            format!("Speed: {}", speed.name()),
+        );
+        self.ui.set_slider(
+            SliderId::Timeline,
+            self.maze.steps_taken(),
+            self.maze.steps_recorded() + 1,
+            format!(
+                "Step: {}/{}",
+                self.maze.steps_taken(),
+                self.maze.steps_recorded()
+            ),
+        );
+
+        let algorithm_names: Vec<&str> = Algorithm::all().iter().map(Algorithm::name).collect();
+        let topology_names: Vec<&str> = Topology::all().iter().map(Topology::name).collect();
+        let solver_names: Vec<&str> = Solver::all().iter().map(Solver::name).collect();
+        let theme_names: Vec<&str> = Theme::all().iter().map(|&(name, _)| name).collect();
+
+        self.ui.set_dropdown(
+            DropdownId::Algorithm,
+            &algorithm_names,
+            self.algorithm_index(),
+        );
+        self.ui.set_dropdown(
+            DropdownId::Topology,
+            &topology_names,
+            Topology::all()
+                .iter()
+                .position(|&other| other == topology)
+                .unwrap_or(0),
+        );
+        self.ui.set_dropdown(
+            DropdownId::Solver,
+            &solver_names,
+            Solver::all()
+                .iter()
+                .position(|&solver| solver == overlays.solver)
+                .unwrap_or(0),
+        );
+        self.ui.set_dropdown(
+            DropdownId::Theme,
+            &theme_names,
+            Theme::all()
+                .iter()
+                .position(|&(_, other)| other == theme)
+                .unwrap_or(0),
+        );
+
+        self.ui
+            .set_spinner(SpinnerId::Width, width, MAZE_MIN_SIZE, MAZE_MAX_SIZE);
+        self.ui
+            .set_spinner(SpinnerId::Height, height, MAZE_MIN_SIZE, MAZE_MAX_SIZE);
+
+        self.ui.set_checkbox(CheckboxId::KeepSquare, keep_square);
+        self.ui
+            .set_checkbox(CheckboxId::Solution, overlays.solution);
+        self.ui.set_checkbox(CheckboxId::Heatmap, overlays.heatmap);
+    }
+
+    fn window_layout(&self) -> WindowLayout {
+        let collapsed_layout;
+        let layout = if self.is_panel_open {
+            &self.layout
+        } else {
+            collapsed_layout = self.layout.without_panel();
+            &collapsed_layout
+        };
+
+        layout.apply(
+            self.window_width,
+            self.window_height,
+            self.maze.width() as usize,
+            self.maze.height() as usize,
+        )
+    }
+
+    /// The cell under the mouse, unless a widget or the help is over it
+    fn hovered_cell(&self, window_layout: &WindowLayout) -> Option<(MazePoint, WindowPoint)> {
+        let mouse = self.mouse?;
+
+        if self.is_showing_help || self.ui.is_over_widget(mouse) {
+            return None;
+        }
+
+        let cell = window_layout
+            .maze
+            .cell_at(mouse)
+            .filter(|&cell| self.maze.is_point_inside(cell))?;
+
+        Some((cell, mouse))
+    }
+
+    /// Changes the maze's size, and resets it right away if `regenerate` is set
+    fn resize_maze(&mut self, regenerate: bool, width: i64, height: i64) {
+        let maze_settings = self.next_maze_settings();
+        let resized = maze_settings.resized(width, height);
+
+        if (resized.width, resized.height) == (maze_settings.width, maze_settings.height) {
+            return;
+        }
+
+        self.change_maze_settings(regenerate, |settings| *settings = resized);
+    }
+
+    /// Saves an image of the maze as it's shown, at a fixed size rather than the window's
+    fn export_png(&self) {
+        let path = PathBuf::from(format!("maze-{}.png", self.maze_settings.seed));
+
+        let ViewSettings {
+            theme, overlays, ..
+        } = self.view_settings;
+
+        match export::export(&path, &self.maze, &self.layout, &theme, overlays) {
+            Ok(()) => println!("Saved {}", path.display()),
+            Err(e) => eprintln!("{}: {}", path.display(), e),
+        }
+    }
+
+    /// Saves the maze and the state of its generation, so it can be continued with `--load`
+    fn save_maze(&self) {
+        let path = PathBuf::from(format!("maze-{}.json", self.maze_settings.seed));
+        let json = save::to_json(&self.maze, self.maze_settings.seed, &self.rng);
+
+        match fs::write(&path, json) {
+            Ok(()) => println!("Saved {}", path.display()),
+            Err(e) => eprintln!("{}: {}", path.display(), e),
+        }
+    }
+
+    /// Copies a share code for the maze, which can be pasted into another instance
+    fn copy_code(&self) {
+        if !self.maze.is_finished() {
+            eprintln!("Only finished mazes can be shared");
+            return;
+        }
+
+        let code = match share::to_code(&self.maze) {
+            Ok(code) => code,
+
+            Err(e) => {
+                eprintln!("{}", e);
+                return;
+            }
+        };
+
+        match self.clipboard.set_text(&code) {
+            Ok(()) => println!("Copied maze code {}", code),
+            Err(e) => eprintln!("Failed to copy maze code: {}", e),
+        }
+    }
+
+    /// Replaces the maze with one from a share code on the clipboard
+    fn paste_code(&mut self) {
+        let code = match self.clipboard.text() {
+            Ok(code) => code,
+
+            Err(e) => {
+                eprintln!("Failed to paste maze code: {}", e);
+                return;
+            }
+        };
+
+        if let Err(e) = self.load_code(&code) {
+            eprintln!("{}", e);
+        }
+    }
+
+    /// Replaces the maze with one from a share code
+    ///
+    /// The seed is kept, since the code doesn't include one and the maze is already finished.
+    fn load_code(&mut self, code: &str) -> Result<(), ShareError> {
+        let maze = share::from_code(code)?;
+
+        self.maze_settings = MazeSettings::from_maze(&maze, self.maze_settings.seed);
+        self.pending_maze_settings = None;
+        self.replace_maze(maze);
+        self.is_auto_stepping = false;
+
+        Ok(())
+    }
 }
 
 impl<'ttf> StableLoop for App<'ttf> {
@@ -120,36 +854,159 @@ impl<'ttf> StableLoop for App<'ttf> {
     }
 
     fn update(&mut self, ctx: &mut Self::Ctx, _current_fps: usize) -> bool {
-        let event_pump = ctx.event_pump();
+        // The widgets move with the window, so they're placed before handling the mouse
+        let window_layout = self.window_layout();
+        self.ui
+            .apply_layout(&window_layout.ui, &window_layout.panel);
 
-        let keep_going = self.handle_events(event_pump.poll_iter());
+        let keep_going = self.handle_events(ctx);
 
         if !keep_going {
             return false;
         }
 
-        // self.ui.update();
+        if self.is_auto_stepping && !self.is_editing && !self.maze.is_finished() {
+            self.time_generation(Self::auto_step);
+        }
+
+        self.sync_ui();
+        self.ui.update();
 
         true
     }
 
-    fn draw(&mut self, ctx: &mut Self::Ctx, _current_fps: usize) {
+    fn draw(&mut self, ctx: &mut Self::Ctx, current_fps: usize) {
         let canvas = ctx.canvas();
 
-        let window_layout = self.layout.apply(
-            self.window_width,
-            self.window_height,
-            self.maze.width() as usize,
-            self.maze.height() as usize,
-        );
+        let window_layout = self.window_layout();
 
-        if let Err(e) = self
-            .graphics
-            .draw(canvas, &window_layout, &self.maze, &self.ui)
-        {
+        if let Err(e) = self.graphics.draw(
+            canvas,
+            &window_layout,
+            &self.maze,
+            self.view_settings.overlays,
+            &self.ui,
+            self.is_showing_help.then(|| self.key_map.help()).as_deref(),
+        ) {
             eprintln!("Failed to draw app: {}", e);
         }
 
+        if self.is_editing {
+            let analysis = Analysis::cached(
+                &mut self.analysis,
+                &self.maze,
+                self.view_settings.overlays.solver,
+            );
+            let lines = editor::notice_lines(&self.maze, analysis);
+
+            if let Err(e) = self
+                .graphics
+                .draw_notice(canvas, &window_layout.maze, &lines)
+            {
+                eprintln!("Failed to draw editor notice: {}", e);
+            }
+        }
+
+        if let Some((cell, mouse)) = self.hovered_cell(&window_layout) {
+            let analysis = Analysis::cached(
+                &mut self.analysis,
+                &self.maze,
+                self.view_settings.overlays.solver,
+            );
+            let lines = inspect::cell_lines(&self.maze, cell, analysis);
+
+            if let Err(e) =
+                self.graphics
+                    .draw_tooltip(canvas, &window_layout.maze, cell, mouse, &lines)
+            {
+                eprintln!("Failed to draw tooltip: {}", e);
+            }
+        }
+
+        if self.is_showing_hud {
+            let lines = hud::lines(
+                current_fps,
+                self.generation_stats,
+                &self.maze,
+                self.maze_settings.seed,
+            );
+
+            if let Err(e) = self.graphics.draw_hud(canvas, &window_layout.maze, &lines) {
+                eprintln!("Failed to draw HUD: {}", e);
+            }
+        }
+
         canvas.present();
     }
 }
+
+/// Everything needed to generate a new maze
+#[derive(Clone)]
+pub struct MazeSettings {
+    pub width: i64,
+    pub height: i64,
+    /// Make the maze square whenever it's resized
+    pub keep_square: bool,
+
+    /// The shape of the maze, resampled to its width and height
+    pub mask: Option<Mask>,
+    pub topology: Topology,
+    pub algorithm: Algorithm,
+    /// Generating with the same seed and settings always results in the same maze
+    pub seed: u64,
+}
+
+impl MazeSettings {
+    /// The settings that generate a maze of the same shape, size and kind as an existing one
+    pub fn from_maze(maze: &Maze, seed: u64) -> Self {
+        Self {
+            width: maze.width(),
+            height: maze.height(),
+            keep_square: maze.width() == maze.height(),
+            mask: Some(maze.mask().clone()),
+            topology: maze.topology(),
+            algorithm: maze.algorithm(),
+            seed,
+        }
+    }
+
+    /// The same settings with a new size, clamped to the allowed range
+    ///
+    /// When keeping the maze square, the dimension that was changed is applied to both.
+    pub fn resized(&self, mut width: i64, mut height: i64) -> Self {
+        if self.keep_square {
+            let size = if height != self.height { height } else { width };
+
+            width = size;
+            height = size;
+        }
+
+        Self {
+            width: width.clamp(MAZE_MIN_SIZE, MAZE_MAX_SIZE),
+            height: height.clamp(MAZE_MIN_SIZE, MAZE_MAX_SIZE),
+            ..self.clone()
+        }
+    }
+
+    pub fn build_maze(&self) -> Result<Maze, MaskError> {
+        let mask = match &self.mask {
+            Some(mask) => mask.resized(self.width, self.height)?,
+            None => Mask::full(self.width, self.height),
+        };
+
+        Ok(Maze::with_mask(mask, self.topology, self.algorithm))
+    }
+
+    pub fn build_rng(&self) -> ChaCha8Rng {
+        ChaCha8Rng::seed_from_u64(self.seed)
+    }
+}
+
+/// Everything about how the maze is shown, which can change without regenerating it
+#[derive(Clone, Copy)]
+pub struct ViewSettings {
+    /// How many generation steps to take every update while auto-stepping
+    pub speed: Speed,
+    pub theme: Theme,
+    pub overlays: Overlays,
+}
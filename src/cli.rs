@@ -0,0 +1,200 @@
+use std::path::PathBuf;
+
+use maze_gen::args::{ArgParser, ArgsError};
+use maze_gen::maze::{Algorithm, Topology};
+use maze_gen::solve::Solver;
+
+use crate::speed::Speed;
+use crate::theme::Theme;
+use crate::{KEY_MAP_FILE, MAZE_MAX_SIZE, MAZE_MIN_SIZE};
+
+/// Options given on the command line, left empty when not given
+#[derive(Default)]
+pub struct Args {
+    pub maze_width: Option<i64>,
+    pub maze_height: Option<i64>,
+    pub mask: Option<PathBuf>,
+    pub topology: Option<Topology>,
+    pub algorithm: Option<Algorithm>,
+    pub seed: Option<u64>,
+    /// Continue a maze saved to a file, instead of generating a new one
+    pub load: Option<PathBuf>,
+    /// Show a finished maze from a share code, instead of generating a new one
+    pub code: Option<String>,
+    /// Show a maze read from an exported image, instead of generating a new one
+    pub import: Option<PathBuf>,
+    pub speed: Option<Speed>,
+    pub theme: Option<Theme>,
+    pub window_size: Option<(u32, u32)>,
+    pub fullscreen: bool,
+    /// Rebind keys from a key map file
+    pub keys: Option<PathBuf>,
+    /// Save an image of the generated maze instead of opening a window
+    pub export: Option<PathBuf>,
+    /// Record the generation as an animation instead of opening a window
+    pub record: Option<PathBuf>,
+    pub frame_skip: Option<u32>,
+    pub frame_delay: Option<u32>,
+    pub solution: bool,
+    pub solver: Option<Solver>,
+    pub heatmap: bool,
+    pub help: bool,
+}
+
+impl Args {
+    /// Parses the arguments, not including the program name
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, ArgsError> {
+        let mut args = ArgParser::new(args);
+        let mut parsed = Self::default();
+
+        while let Some(option) = args.next_option()? {
+            match option.as_str() {
+                "-W" | "--width" => {
+                    parsed.maze_width = Some(args.value_in(MAZE_MIN_SIZE..=MAZE_MAX_SIZE)?);
+                }
+
+                "-H" | "--height" => {
+                    parsed.maze_height = Some(args.value_in(MAZE_MIN_SIZE..=MAZE_MAX_SIZE)?);
+                }
+
+                "-m" | "--mask" => {
+                    parsed.mask = Some(PathBuf::from(args.value()?));
+                }
+
+                "-t" | "--topology" => {
+                    parsed.topology = Some(args.named_value(Topology::from_name)?);
+                }
+
+                "-a" | "--algorithm" => {
+                    parsed.algorithm = Some(args.named_value(Algorithm::from_name)?);
+                }
+
+                "-s" | "--seed" => {
+                    parsed.seed = Some(args.parsed_value()?);
+                }
+
+                "-l" | "--load" => {
+                    parsed.load = Some(PathBuf::from(args.value()?));
+                }
+
+                "-c" | "--code" => {
+                    parsed.code = Some(args.value()?);
+                }
+
+                "-i" | "--import" => {
+                    parsed.import = Some(PathBuf::from(args.value()?));
+                }
+
+                "-S" | "--speed" => {
+                    parsed.speed = Some(args.named_value(Speed::from_name)?);
+                }
+
+                "--theme" => {
+                    parsed.theme = Some(args.named_value(Theme::from_name)?);
+                }
+
+                "--window-size" => {
+                    let value = args.value()?;
+                    let size = value
+                        .split_once('x')
+                        .and_then(|(width, height)| {
+                            Some((width.parse().ok()?, height.parse().ok()?))
+                        })
+                        .filter(|&(width, height)| width > 0 && height > 0)
+                        .ok_or_else(|| args.invalid_value(value))?;
+
+                    parsed.window_size = Some(size);
+                }
+
+                "-f" | "--fullscreen" => {
+                    parsed.fullscreen = true;
+                }
+
+                "-k" | "--keys" => {
+                    parsed.keys = Some(PathBuf::from(args.value()?));
+                }
+
+                "-e" | "--export" => {
+                    parsed.export = Some(PathBuf::from(args.value()?));
+                }
+
+                "-r" | "--record" => {
+                    parsed.record = Some(PathBuf::from(args.value()?));
+                }
+
+                "--frame-skip" => {
+                    parsed.frame_skip = Some(args.value_in(1..=u32::MAX)?);
+                }
+
+                "--frame-delay" => {
+                    parsed.frame_delay = Some(args.parsed_value()?);
+                }
+
+                "--solution" => {
+                    parsed.solution = true;
+                }
+
+                "--solver" => {
+                    parsed.solver = Some(args.named_value(Solver::from_name)?);
+                }
+
+                "--heatmap" => {
+                    parsed.heatmap = true;
+                }
+
+                "-h" | "--help" => {
+                    parsed.help = true;
+                }
+
+                _ => return Err(args.unknown_option()),
+            }
+        }
+
+        Ok(parsed)
+    }
+
+    pub fn usage() -> String {
+        let algorithms: Vec<&str> = Algorithm::all().iter().map(Algorithm::name).collect();
+        let topologies: Vec<&str> = Topology::all().iter().map(Topology::name).collect();
+        let themes: Vec<&str> = Theme::all().iter().map(|&(name, _)| name).collect();
+        let solvers: Vec<&str> = Solver::all().iter().map(Solver::name).collect();
+
+        format!(
+            "\
+Usage: maze_gen [OPTIONS]
+
+Options:
+  -W, --width <CELLS>        Maze width, from {min} to {max}
+  -H, --height <CELLS>       Maze height, from {min} to {max}
+  -m, --mask <FILE>          Shape the maze with an ASCII template or a black-and-white PNG
+  -t, --topology <NAME>      One of: {topologies}
+  -a, --algorithm <NAME>     One of: {algorithms}
+  -s, --seed <NUMBER>        Seed for a reproducible maze (random by default)
+  -l, --load <FILE>          Continue a maze saved as JSON, ignoring the options above
+  -c, --code <CODE>          Show a finished maze from a share code, ignoring the options above
+  -i, --import <FILE>        Show a maze from a .png exported by this app, as big as --width and --height
+  -S, --speed <STEPS>        Generation steps per frame, which may be fractional like 0.1,
+                             or max for as many as fit in a frame, 0 to start paused
+      --theme <NAME>         One of: {themes}
+      --window-size <WxH>    Initial window size in pixels, e.g. 1024x768
+  -f, --fullscreen           Start in fullscreen
+  -k, --keys <FILE>          Rebind keys, with lines like `step = Space` (default: {key_map_file})
+  -e, --export <FILE>        Save an image of the finished maze as .svg or .png, without a window
+  -r, --record <FILE>        Record the generation as a .gif or numbered .png frames, without a window
+      --frame-skip <STEPS>   Generation steps between recorded frames
+      --frame-delay <MS>     How long every recorded frame is shown, in milliseconds
+      --solution             Draw the solution once the maze is finished
+      --solver <NAME>        How the solution is found, one of: {solvers}
+      --heatmap              Color cells by distance from the start once the maze is finished
+  -h, --help                 Print this help
+",
+            min = MAZE_MIN_SIZE,
+            max = MAZE_MAX_SIZE,
+            topologies = topologies.join(", "),
+            algorithms = algorithms.join(", "),
+            themes = themes.join(", "),
+            solvers = solvers.join(", "),
+            key_map_file = KEY_MAP_FILE,
+        )
+    }
+}
@@ -0,0 +1,61 @@
+use sdl2::render::WindowCanvas;
+
+/// The system clipboard, or the browser's in the emscripten build
+///
+/// SDL's clipboard only lives inside the page on the web, so the browser is asked directly instead.
+pub struct Clipboard {
+    #[cfg(not(target_os = "emscripten"))]
+    clipboard_util: sdl2::clipboard::ClipboardUtil,
+}
+
+impl Clipboard {
+    pub fn new(canvas: &WindowCanvas) -> Self {
+        #[cfg(target_os = "emscripten")]
+        let _ = canvas;
+
+        Self {
+            #[cfg(not(target_os = "emscripten"))]
+            clipboard_util: canvas.window().subsystem().clipboard(),
+        }
+    }
+
+    #[cfg(not(target_os = "emscripten"))]
+    pub fn set_text(&self, text: &str) -> Result<(), String> {
+        self.clipboard_util.set_clipboard_text(text)
+    }
+
+    /// Browsers may refuse to write to the clipboard, so the text is shown to be copied by hand instead
+    ///
+    /// The text is embedded in a script, so it mustn't contain quotes or backslashes.
+    #[cfg(target_os = "emscripten")]
+    pub fn set_text(&self, text: &str) -> Result<(), String> {
+        emscripten_functions::emscripten::run_script(format!(
+            r##"
+                let text = "{0}";
+                let show = () => window.prompt("Copy this maze code:", text);
+                if (navigator.clipboard) {{
+                    navigator.clipboard.writeText(text).catch(show);
+                }} else {{
+                    show();
+                }}
+            "##,
+            text
+        ));
+
+        Ok(())
+    }
+
+    #[cfg(not(target_os = "emscripten"))]
+    pub fn text(&self) -> Result<String, String> {
+        self.clipboard_util.clipboard_text()
+    }
+
+    /// Browsers only let pages read the clipboard asynchronously, so the text is asked for instead
+    #[cfg(target_os = "emscripten")]
+    pub fn text(&self) -> Result<String, String> {
+        emscripten_functions::emscripten::run_script_string(
+            r#"window.prompt("Paste a maze code:") || """#,
+        )
+        .ok_or_else(|| "Nothing was pasted".to_string())
+    }
+}
@@ -18,6 +18,7 @@ impl Context {
         window_title: &str,
         window_width: u32,
         window_height: u32,
+        fullscreen: bool,
     ) -> Result<(Self, Sdl2TtfContext), InitError> {
         sdl2::hint::set("SDL_WINDOWS_DPI_AWARENESS", "permonitorv2");
 
@@ -27,12 +28,14 @@ impl Context {
 
         let video_subsystem = sdl_ctx.video().map_err(InitError::VideoSubsystem)?;
 
-        let window = video_subsystem
-            .window(window_title, window_width, window_height)
-            .position_centered()
-            .resizable()
-            .build()
-            .map_err(InitError::Window)?;
+        let mut window_builder = video_subsystem.window(window_title, window_width, window_height);
+        window_builder.position_centered().resizable();
+
+        if fullscreen {
+            window_builder.fullscreen_desktop();
+        }
+
+        let window = window_builder.build().map_err(InitError::Window)?;
 
         let canvas = window
             .into_canvas()
@@ -0,0 +1,318 @@
+use maze_gen::maze::{Maze, Orientation};
+use maze_gen::point::MazePoint;
+
+use crate::analysis::Analysis;
+use crate::layout::WindowMazeLayout;
+use crate::point_new::Point;
+use crate::window_point::WindowPoint;
+use crate::EDIT_EDGE_REACH;
+
+/// A change made to the maze by hand, which can be undone and redone
+enum Edit {
+    /// Walls that were all added or all removed, between pairs of adjacent cells
+    Walls {
+        walls: Vec<(MazePoint, MazePoint)>,
+        is_wall: bool,
+        /// Passages beneath crossings that a new wall closed off, and so were filled in,
+        /// with the walls across their ends in `walls`
+        under_passages: Vec<(MazePoint, Orientation)>,
+    },
+    Start {
+        from: MazePoint,
+        to: MazePoint,
+    },
+    Goal {
+        from: MazePoint,
+        to: MazePoint,
+    },
+}
+
+impl Edit {
+    fn apply(&self, maze: &mut Maze) {
+        match *self {
+            Edit::Walls {
+                ref walls,
+                is_wall,
+                ref under_passages,
+            } => {
+                for &(cell1, cell2) in walls {
+                    maze.set_wall(cell1, cell2, is_wall);
+                }
+
+                for &(cell, _) in under_passages {
+                    maze.remove_under_passage(cell);
+                }
+            }
+            Edit::Start { to, .. } => maze.set_start(to),
+            Edit::Goal { to, .. } => maze.set_goal(to),
+        }
+    }
+
+    fn revert(&self, maze: &mut Maze) {
+        match *self {
+            Edit::Walls {
+                ref walls,
+                is_wall,
+                ref under_passages,
+            } => {
+                for &(cell1, cell2) in walls {
+                    maze.set_wall(cell1, cell2, !is_wall);
+                }
+
+                for &(cell, orientation) in under_passages {
+                    maze.set_under_passage(cell, orientation);
+                }
+            }
+            Edit::Start { from, .. } => maze.set_start(from),
+            Edit::Goal { from, .. } => maze.set_goal(from),
+        }
+    }
+}
+
+/// The edits made to the maze, so they can be undone and redone
+///
+/// Dragging across walls is a single edit that paints them all the same way as the first one,
+/// which is toggled. A crossing only stays one while it's open on all four sides, so adding
+/// a wall next to one fills in the passage beneath it, walling off the ends of the tunnel
+/// so they don't open into the passage on top, as part of the same edit.
+#[derive(Default)]
+pub struct EditHistory {
+    undo_stack: Vec<Edit>,
+    redo_stack: Vec<Edit>,
+    /// The walls painted so far while dragging
+    stroke: Option<Edit>,
+}
+
+impl EditHistory {
+    /// Toggles a wall and starts painting walls that way
+    pub fn start_stroke(&mut self, maze: &mut Maze, wall: (MazePoint, MazePoint)) {
+        let (cell1, cell2) = wall;
+
+        self.stroke = Some(Edit::Walls {
+            walls: Vec::new(),
+            is_wall: maze.is_passage(cell1, cell2),
+            under_passages: Vec::new(),
+        });
+
+        self.paint(maze, wall);
+    }
+
+    /// Adds or removes a wall like the rest of the stroke, unless it already is that way
+    pub fn paint(&mut self, maze: &mut Maze, (cell1, cell2): (MazePoint, MazePoint)) {
+        let Some(Edit::Walls {
+            walls,
+            is_wall,
+            under_passages,
+        }) = &mut self.stroke
+        else {
+            return;
+        };
+
+        if maze.is_passage(cell1, cell2) == *is_wall {
+            maze.set_wall(cell1, cell2, *is_wall);
+            walls.push((cell1, cell2));
+
+            if *is_wall {
+                for cell in [cell1, cell2] {
+                    let Some(orientation) = maze.remove_under_passage(cell) else {
+                        continue;
+                    };
+
+                    under_passages.push((cell, orientation));
+
+                    // Without the tunnel, its ends would open into the passage on top
+                    let sides = match orientation {
+                        Orientation::Horizontal => [(-1, 0), (1, 0)],
+                        Orientation::Vertical => [(0, -1), (0, 1)],
+                    };
+
+                    for dir in sides {
+                        if maze.is_passage(cell, cell + dir) {
+                            maze.set_wall(cell, cell + dir, true);
+                            walls.push((cell, cell + dir));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn is_painting(&self) -> bool {
+        self.stroke.is_some()
+    }
+
+    pub fn end_stroke(&mut self) {
+        if let Some(stroke) = self.stroke.take() {
+            if !matches!(&stroke, Edit::Walls { walls, .. } if walls.is_empty()) {
+                self.push(stroke);
+            }
+        }
+    }
+
+    pub fn set_start(&mut self, maze: &mut Maze, cell: MazePoint) {
+        let edit = Edit::Start {
+            from: maze.start(),
+            to: cell,
+        };
+
+        if cell != maze.start() && maze.is_point_inside(cell) {
+            edit.apply(maze);
+            self.push(edit);
+        }
+    }
+
+    pub fn set_goal(&mut self, maze: &mut Maze, cell: MazePoint) {
+        let edit = Edit::Goal {
+            from: maze.goal(),
+            to: cell,
+        };
+
+        if cell != maze.goal() && maze.is_point_inside(cell) {
+            edit.apply(maze);
+            self.push(edit);
+        }
+    }
+
+    pub fn undo(&mut self, maze: &mut Maze) {
+        if let Some(edit) = self.undo_stack.pop() {
+            edit.revert(maze);
+            self.redo_stack.push(edit);
+        }
+    }
+
+    pub fn redo(&mut self, maze: &mut Maze) {
+        if let Some(edit) = self.redo_stack.pop() {
+            edit.apply(maze);
+            self.undo_stack.push(edit);
+        }
+    }
+
+    /// A new edit can't be followed by the ones that were undone before it
+    fn push(&mut self, edit: Edit) {
+        self.undo_stack.push(edit);
+        self.redo_stack.clear();
+    }
+}
+
+/// The wall along the cell edge closest to a position in the window, if it's close enough
+/// and there's a cell on its other side
+pub fn wall_at(
+    maze: &Maze,
+    layout: &WindowMazeLayout,
+    point: WindowPoint,
+) -> Option<(MazePoint, MazePoint)> {
+    let cell = layout
+        .cell_at(point)
+        .filter(|&cell| maze.is_point_inside(cell))?;
+    let rect = layout.cell_rect(Point::new(cell.x as usize, cell.y as usize));
+
+    let (x, y) = (point.x - rect.x() as i64, point.y - rect.y() as i64);
+    let (width, height) = (rect.width() as i64, rect.height() as i64);
+
+    let (distance, dir) = [
+        (x, (-1, 0)),
+        (width - x, (1, 0)),
+        (y, (0, -1)),
+        (height - y, (0, 1)),
+    ]
+    .into_iter()
+    .min_by_key(|&(distance, _)| distance)?;
+
+    let reach = (width.min(height) as f64 * EDIT_EDGE_REACH) as i64;
+    let neighbour = cell + dir;
+
+    (distance <= reach && maze.is_point_inside(neighbour)).then_some((cell, neighbour))
+}
+
+/// The lines shown while editing, with how to edit and then anything wrong with the maze
+pub fn notice_lines(maze: &Maze, analysis: &Analysis) -> Vec<String> {
+    let mut lines = vec![
+        "Click or drag along walls to toggle them".to_string(),
+        "Right-click to move the start".to_string(),
+        "Shift + right-click to move the goal".to_string(),
+    ];
+
+    lines.extend(warnings(maze, analysis));
+
+    lines
+}
+
+/// Problems with a hand-made maze, which it can still be shown and saved with
+fn warnings(maze: &Maze, analysis: &Analysis) -> Vec<String> {
+    let distances = analysis.distances();
+    let unreachable = maze.cell_count().saturating_sub(distances.len());
+
+    let mut warnings = Vec::new();
+
+    if !distances.contains_key(&maze.goal()) {
+        warnings.push("The goal can't be reached".to_string());
+    }
+
+    if unreachable > 0 {
+        warnings.push(format!("{} cells can't be reached", unreachable));
+    }
+
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use maze_gen::maze::{Algorithm, Topology};
+
+    use super::*;
+
+    /// A maze whose middle cell is open on all four sides, with a passage beneath it
+    fn crossing_maze() -> Maze {
+        let mut maze = Maze::new(3, 3, Topology::Weave, Algorithm::RecursiveBacktracker);
+        let middle = MazePoint::new(1, 1);
+
+        for dir in [(-1, 0), (1, 0), (0, -1), (0, 1)] {
+            maze.set_wall(middle, middle + dir, false);
+        }
+        maze.set_under_passage(middle, Orientation::Horizontal);
+
+        maze
+    }
+
+    fn open_sides(maze: &Maze, cell: MazePoint) -> Vec<(i64, i64)> {
+        [(-1, 0), (1, 0), (0, -1), (0, 1)]
+            .into_iter()
+            .filter(|&dir| maze.is_passage(cell, cell + dir))
+            .collect()
+    }
+
+    #[test]
+    fn walls_next_to_crossings_close_off_the_tunnel() {
+        let mut maze = crossing_maze();
+        let mut history = EditHistory::default();
+        let middle = MazePoint::new(1, 1);
+
+        history.start_stroke(&mut maze, (middle, MazePoint::new(1, 0)));
+        history.end_stroke();
+
+        assert!(maze.under_passage(middle).is_none());
+        assert_eq!(open_sides(&maze, middle), [(0, 1)]);
+
+        history.undo(&mut maze);
+
+        assert!(maze.under_passage(middle) == Some(Orientation::Horizontal));
+        assert_eq!(open_sides(&maze, middle).len(), 4);
+
+        history.redo(&mut maze);
+
+        assert!(maze.under_passage(middle).is_none());
+        assert_eq!(open_sides(&maze, middle), [(0, 1)]);
+    }
+
+    #[test]
+    fn removing_walls_leaves_crossings_alone() {
+        let mut maze = crossing_maze();
+        let mut history = EditHistory::default();
+
+        history.start_stroke(&mut maze, (MazePoint::new(0, 0), MazePoint::new(1, 0)));
+        history.end_stroke();
+
+        assert!(maze.under_passage(MazePoint::new(1, 1)) == Some(Orientation::Horizontal));
+        assert_eq!(open_sides(&maze, MazePoint::new(1, 1)).len(), 4);
+    }
+}
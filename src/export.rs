@@ -0,0 +1,270 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
+
+use gif::Repeat;
+use png::{BitDepth, ColorType};
+use rand::Rng;
+use sdl2::pixels::PixelFormatEnum;
+use sdl2::surface::Surface;
+
+use maze_gen::maze::Maze;
+
+use crate::graphics::{DrawError, MazeGraphics};
+use crate::layout::Layout;
+use crate::overlays::Overlays;
+use crate::svg;
+use crate::theme::Theme;
+use crate::{EXPORT_SIZE, GIF_SPEED, RECORDING_FINAL_FRAME_DELAY, RECORDING_SIZE};
+
+/// Saves an image of the maze, in the format matching the file's extension
+pub fn export(
+    path: &Path,
+    maze: &Maze,
+    layout: &Layout,
+    theme: &Theme,
+    overlays: Overlays,
+) -> Result<(), ExportError> {
+    let extension = file_extension(path);
+    let layout = &layout.without_ui();
+
+    let (width, height) =
+        layout.image_size(EXPORT_SIZE, maze.width() as usize, maze.height() as usize);
+
+    match extension.as_str() {
+        "svg" => {
+            let svg = svg::render(maze, layout, theme, overlays, width, height);
+
+            fs::write(path, svg).map_err(ExportError::Io)
+        }
+
+        "png" => {
+            let pixels = draw_image(maze, layout, theme, overlays, width, height)?;
+
+            write_png(path, &pixels, width, height)
+        }
+
+        _ => Err(ExportError::UnsupportedFormat(extension)),
+    }
+}
+
+/// How to record the generation of a maze as an animation
+#[derive(Clone, Copy)]
+pub struct Recording {
+    /// How many generation steps to take between frames
+    pub frame_skip: u32,
+    /// How long every frame is shown, in milliseconds
+    pub frame_delay: u32,
+}
+
+/// Records every step of generating the maze, leaving it finished, as an animated GIF
+/// or as numbered PNG images next to the path (`maze.png` becomes `maze-0000.png`, ...)
+///
+/// The last frame shows the finished maze, held for longer.
+pub fn record(
+    path: &Path,
+    maze: &mut Maze,
+    rng: &mut impl Rng,
+    layout: &Layout,
+    theme: &Theme,
+    overlays: Overlays,
+    recording: Recording,
+) -> Result<(), ExportError> {
+    let extension = file_extension(path);
+    let layout = &layout.without_ui();
+
+    let (width, height) = layout.image_size(
+        RECORDING_SIZE,
+        maze.width() as usize,
+        maze.height() as usize,
+    );
+
+    let mut frame_writer = match extension.as_str() {
+        "gif" => {
+            let file = File::create(path).map_err(ExportError::Io)?;
+
+            let mut encoder =
+                gif::Encoder::new(BufWriter::new(file), width as u16, height as u16, &[])
+                    .map_err(ExportError::Gif)?;
+            encoder
+                .set_repeat(Repeat::Infinite)
+                .map_err(ExportError::Gif)?;
+
+            FrameWriter::Gif(encoder)
+        }
+
+        "png" => FrameWriter::PngSequence {
+            path: path.to_path_buf(),
+            next_frame: 0,
+        },
+
+        _ => return Err(ExportError::UnsupportedFormat(extension)),
+    };
+
+    loop {
+        let pixels = draw_image(maze, layout, theme, overlays, width, height)?;
+
+        let delay = if maze.is_finished() {
+            RECORDING_FINAL_FRAME_DELAY
+        } else {
+            recording.frame_delay
+        };
+
+        frame_writer.write_frame(&pixels, width, height, delay)?;
+
+        if maze.is_finished() {
+            break;
+        }
+
+        for _ in 0..recording.frame_skip.max(1) {
+            maze.step(rng);
+        }
+    }
+
+    frame_writer.finish()
+}
+
+enum FrameWriter {
+    Gif(gif::Encoder<BufWriter<File>>),
+    PngSequence { path: PathBuf, next_frame: u32 },
+}
+
+impl FrameWriter {
+    fn write_frame(
+        &mut self,
+        pixels: &[u8],
+        width: u32,
+        height: u32,
+        delay: u32,
+    ) -> Result<(), ExportError> {
+        match self {
+            Self::Gif(encoder) => {
+                let mut frame =
+                    gif::Frame::from_rgb_speed(width as u16, height as u16, pixels, GIF_SPEED);
+                // GIF delays are in hundredths of a second
+                frame.delay = (delay / 10).min(u16::MAX as u32) as u16;
+
+                encoder.write_frame(&frame).map_err(ExportError::Gif)
+            }
+
+            Self::PngSequence { path, next_frame } => {
+                let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+                let frame_path = path.with_file_name(format!("{}-{:04}.png", stem, next_frame));
+                *next_frame += 1;
+
+                write_png(&frame_path, pixels, width, height)
+            }
+        }
+    }
+
+    fn finish(self) -> Result<(), ExportError> {
+        match self {
+            Self::Gif(encoder) => {
+                encoder.into_inner().map_err(ExportError::Gif)?;
+
+                Ok(())
+            }
+
+            Self::PngSequence { .. } => Ok(()),
+        }
+    }
+}
+
+fn file_extension(path: &Path) -> String {
+    path.extension()
+        .and_then(|extension| extension.to_str())
+        .unwrap_or_default()
+        .to_ascii_lowercase()
+}
+
+/// Draws the maze to an offscreen canvas the same way it's drawn in the window,
+/// returning its pixels as rows of RGB
+fn draw_image(
+    maze: &Maze,
+    layout: &Layout,
+    theme: &Theme,
+    overlays: Overlays,
+    width: u32,
+    height: u32,
+) -> Result<Vec<u8>, ExportError> {
+    let surface =
+        Surface::new(width, height, PixelFormatEnum::RGB24).map_err(ExportError::Surface)?;
+    let mut canvas = surface.into_canvas().map_err(ExportError::Surface)?;
+
+    let window_layout = layout.apply(width, height, maze.width() as usize, maze.height() as usize);
+    MazeGraphics::new(*theme)
+        .draw(&mut canvas, &window_layout, maze, overlays)
+        .map_err(ExportError::Draw)?;
+
+    let surface = canvas.into_surface();
+    let row_length = width as usize * 3;
+    let pitch = surface.pitch() as usize;
+
+    // Rows of the surface may be padded, but the image's rows can't be
+    let pixels = surface.with_lock(|pixels| {
+        pixels
+            .chunks(pitch)
+            .flat_map(|row| &row[..row_length])
+            .copied()
+            .collect()
+    });
+
+    Ok(pixels)
+}
+
+fn write_png(path: &Path, pixels: &[u8], width: u32, height: u32) -> Result<(), ExportError> {
+    let file = File::create(path).map_err(ExportError::Io)?;
+
+    let mut encoder = png::Encoder::new(BufWriter::new(file), width, height);
+    encoder.set_color(ColorType::Rgb);
+    encoder.set_depth(BitDepth::Eight);
+
+    let mut writer = encoder.write_header().map_err(ExportError::Png)?;
+    writer.write_image_data(pixels).map_err(ExportError::Png)?;
+
+    writer.finish().map_err(ExportError::Png)
+}
+
+#[derive(Debug)]
+pub enum ExportError {
+    Io(io::Error),
+    UnsupportedFormat(String),
+    Surface(String),
+    Draw(DrawError),
+    Png(png::EncodingError),
+    Gif(gif::EncodingError),
+}
+
+impl Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Failed to write image: {}", e),
+            Self::UnsupportedFormat(extension) => {
+                write!(
+                    f,
+                    "Unsupported image format '{}', expected svg or png (or gif for recordings)",
+                    extension
+                )
+            }
+            Self::Surface(e) => write!(f, "Failed to create offscreen canvas: {}", e),
+            Self::Draw(e) => write!(f, "Failed to draw image: {}", e),
+            Self::Png(e) => write!(f, "Failed to encode image: {}", e),
+            Self::Gif(e) => write!(f, "Failed to encode animation: {}", e),
+        }
+    }
+}
+
+impl Error for ExportError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+            Self::Draw(e) => Some(e),
+            Self::Png(e) => Some(e),
+            Self::Gif(e) => Some(e),
+
+            Self::UnsupportedFormat(_) | Self::Surface(_) => None,
+        }
+    }
+}
@@ -11,27 +11,31 @@ use sdl2::render::{
 use sdl2::ttf::{Font, FontError, Sdl2TtfContext};
 use sdl2::video::{Window, WindowContext};
 
+use maze_gen::maze::Maze;
+use maze_gen::point::MazePoint;
+use maze_gen::solve::{self, Solver};
+
 use crate::color_blend::ColorBlend;
 use crate::layout::{WindowLayout, WindowMazeLayout};
-use crate::maze::Maze;
-use crate::point::{Convert, MazePoint, WindowPoint};
+use crate::overlays::Overlays;
 use crate::point_new::Point;
-use crate::ui::{ButtonState, Ui};
-use crate::{
-    BACKGROUND_COLOR, CURSOR_COLOR, FONT, FONT_SIZE, GFX_UI_HEIGHT, GFX_UI_WIDTH, GFX_UI_X,
-    GFX_UI_Y, UI_BUTTON_CLICKED_COLOR, UI_BUTTON_COLOR, UI_BUTTON_HIGHLIGHT_COLOR,
-    UI_BUTTON_TEXT_COLOR, UI_COLOR, VISITED_CELL_COLOR, WALL_COLOR,
-};
+use crate::theme::Theme;
+use crate::ui::{Bounds, ButtonState, TextField, Ui};
+use crate::window_point::WindowPoint;
+use crate::{FONT, FONT_SIZE, GFX_HUD_LINE_HEIGHT, GFX_TOOLTIP_OFFSET, TEXT_FIT};
 
 pub struct Graphics<'ttf> {
     font: Font<'ttf, 'static>,
     texture_creator: TextureCreator<WindowContext>,
+    theme: Theme,
+    maze_graphics: MazeGraphics,
 }
 
 impl<'ttf> Graphics<'ttf> {
     pub fn new(
         ttf_ctx: &'ttf Sdl2TtfContext,
         canvas: &WindowCanvas,
+        theme: Theme,
     ) -> Result<Self, FontLoadError> {
         let font = ttf_ctx.load_font(FONT, FONT_SIZE)?;
 
@@ -40,217 +44,560 @@ impl<'ttf> Graphics<'ttf> {
         Ok(Self {
             font,
             texture_creator,
+            theme,
+            maze_graphics: MazeGraphics::new(theme),
         })
     }
 
+    /// Changes the colors of everything drawn from now on
+    pub fn set_theme(&mut self, theme: Theme) {
+        self.theme = theme;
+        self.maze_graphics = MazeGraphics::new(theme);
+    }
+
     pub fn draw(
         &self,
         canvas: &mut Canvas<impl RenderTarget>,
         layout: &WindowLayout,
         maze: &Maze,
+        overlays: Overlays,
         ui: &Ui,
+        help: Option<&[(String, &str)]>,
     ) -> Result<(), DrawError> {
-        canvas.set_draw_color(UI_COLOR);
-        canvas.clear();
+        self.maze_graphics.draw(canvas, layout, maze, overlays)?;
+        self.draw_ui(canvas, layout, ui)?;
 
-        self.draw_maze(canvas, &layout.maze, maze)?;
-        // self.draw_ui(canvas, ui)?;
+        if let Some(help) = help {
+            self.draw_help(canvas, &layout.maze, help)?;
+        }
 
         Ok(())
     }
 
-    fn draw_maze(
+    fn draw_ui(
         &self,
         canvas: &mut Canvas<impl RenderTarget>,
-        layout: &WindowMazeLayout,
-        maze: &Maze,
+        layout: &WindowLayout,
+        ui: &Ui,
     ) -> Result<(), DrawError> {
-        self.draw_maze_background(canvas, layout, maze)?;
+        canvas.set_draw_color(self.theme.ui);
 
-        self.draw_maze_visited_cells(canvas, layout, maze)?;
+        for rect in [layout.ui.rect(), layout.panel.rect()]
+            .into_iter()
+            .flatten()
+        {
+            Self::fill_rect(canvas, rect)?;
+        }
 
-        self.draw_maze_walls(canvas, layout, maze)?;
+        for button in ui.buttons() {
+            let rect = Self::bounds_rect(button.bounds);
 
-        self.draw_maze_cursor(canvas, layout, maze)?;
+            canvas.set_draw_color(if button.is_enabled {
+                self.widget_color(&button.state)
+            } else {
+                self.disabled_widget_color()
+            });
+            Self::fill_rect(canvas, rect)?;
+
+            self.draw_text(canvas, button.text(), rect, true)?;
+        }
+
+        for slider in ui.sliders() {
+            let rect = Self::bounds_rect(slider.bounds);
+
+            canvas.set_draw_color(self.theme.ui_button_clicked);
+            Self::fill_rect(canvas, rect)?;
+
+            // The part of the slider up to its value is filled in like a button
+            let filled_width = (rect.width() as f64 * slider.fraction()).round() as u32;
+
+            if filled_width > 0 {
+                canvas.set_draw_color(if slider.is_enabled {
+                    self.widget_color(&slider.state)
+                } else {
+                    self.disabled_widget_color()
+                });
+                Self::fill_rect(
+                    canvas,
+                    Rect::new(rect.x(), rect.y(), filled_width, rect.height()),
+                )?;
+            }
+
+            self.draw_text(canvas, &slider.text, rect, true)?;
+        }
+
+        for dropdown in ui.dropdowns() {
+            let rect = Self::bounds_rect(dropdown.bounds);
+
+            canvas.set_draw_color(self.widget_color(&dropdown.state));
+            Self::fill_rect(canvas, rect)?;
+
+            self.draw_text(canvas, &dropdown.text(), rect, true)?;
+        }
+
+        for spinner in ui.spinners() {
+            let value_rect = Self::bounds_rect(spinner.value_bounds());
+
+            canvas.set_draw_color(self.theme.ui_button);
+            Self::fill_rect(canvas, value_rect)?;
+
+            self.draw_text(canvas, &spinner.text(), value_rect, true)?;
+
+            for (bounds, state, text) in [
+                (spinner.decrement_bounds(), &spinner.decrement_state, "-"),
+                (spinner.increment_bounds(), &spinner.increment_state, "+"),
+            ] {
+                let rect = Self::bounds_rect(bounds);
+
+                canvas.set_draw_color(self.widget_color(state));
+                Self::fill_rect(canvas, rect)?;
+
+                self.draw_text(canvas, text, rect, true)?;
+            }
+        }
+
+        for checkbox in ui.checkboxes() {
+            let rect = Self::bounds_rect(checkbox.bounds);
+
+            canvas.set_draw_color(self.widget_color(&checkbox.state));
+            Self::fill_rect(canvas, rect)?;
+
+            // The box is a square on the left, with a smaller square in it when checked
+            let box_size = rect.height() / 2;
+            let box_rect = Rect::from_center(
+                (rect.x() + (rect.height() / 2) as i32, rect.center().y()),
+                box_size,
+                box_size,
+            );
+
+            canvas.set_draw_color(self.theme.ui_button_clicked);
+            Self::fill_rect(canvas, box_rect)?;
+
+            if checkbox.is_checked {
+                canvas.set_draw_color(self.theme.ui_button_text);
+                Self::fill_rect(
+                    canvas,
+                    Rect::from_center(box_rect.center(), box_size / 2, box_size / 2),
+                )?;
+            }
+
+            let text_rect = Rect::new(
+                rect.x() + rect.height() as i32,
+                rect.y(),
+                rect.width().saturating_sub(rect.height()).max(1),
+                rect.height(),
+            );
+
+            self.draw_text(canvas, checkbox.text(), text_rect, false)?;
+        }
+
+        for text_field in ui.text_fields() {
+            self.draw_text_field(canvas, text_field)?;
+        }
+
+        // Open dropdowns are drawn last, since their lists cover the maze and other widgets
+        for dropdown in ui.dropdowns().filter(|dropdown| dropdown.is_open) {
+            for (option, (text, state)) in dropdown
+                .options
+                .iter()
+                .zip(&dropdown.option_states)
+                .enumerate()
+            {
+                let rect = Self::bounds_rect(dropdown.option_bounds(option));
+
+                let color = if option == dropdown.selected {
+                    self.theme.ui_button_highlight
+                } else {
+                    self.widget_color(state)
+                };
+
+                canvas.set_draw_color(color);
+                Self::fill_rect(canvas, rect)?;
+
+                self.draw_text(canvas, text, rect, true)?;
+            }
+        }
 
         Ok(())
     }
 
-    fn draw_maze_background(
+    /// Draws a text field's text on the left, scrolled to keep the cursor in view
+    ///
+    /// Only the characters that fit are rendered, since a share code can be wider
+    /// than the largest texture.
+    fn draw_text_field(
         &self,
         canvas: &mut Canvas<impl RenderTarget>,
-        layout: &WindowMazeLayout,
-        maze: &Maze,
+        text_field: &TextField,
     ) -> Result<(), DrawError> {
-        canvas.set_draw_color(BACKGROUND_COLOR);
+        let rect = Self::bounds_rect(text_field.bounds);
 
-        Self::fill_rect(
-            canvas,
-            Rect::new(
-                layout.position.x,
-                layout.position.y,
-                layout.width,
-                layout.height,
-            ),
-        )
+        let color = if text_field.is_focused {
+            self.theme.ui_button_highlight
+        } else {
+            self.widget_color(&text_field.state)
+        };
+
+        canvas.set_draw_color(color);
+        Self::fill_rect(canvas, rect)?;
+
+        // Rejected text is underlined
+        if !text_field.is_valid {
+            let underline_height = (rect.height() / 10).max(1);
+
+            canvas.set_draw_color(self.theme.ui_invalid);
+            Self::fill_rect(
+                canvas,
+                Rect::new(
+                    rect.x(),
+                    rect.bottom() - underline_height as i32,
+                    rect.width(),
+                    underline_height,
+                ),
+            )?;
+        }
+
+        if text_field.text.is_empty() && !text_field.is_focused {
+            return self.draw_text(canvas, text_field.placeholder(), rect, false);
+        }
+
+        // The text is shrunk to fit the height like other widgets, but never to fit the width
+        let margin = (rect.width() as f64 * (1.0 - TEXT_FIT) / 2.0) as i32;
+        let scale = (rect.height() as f64 * TEXT_FIT / self.font.height().max(1) as f64).min(1.0);
+        let available_width = ((rect.width() as i32 - margin * 2).max(1) as f64 / scale) as u32;
+
+        let char_widths = text_field
+            .text
+            .chars()
+            .map(|c| self.font.size_of_char(c).map(|(width, _)| width))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(DrawError::FontRendering)?;
+
+        // Everything up to the cursor that fits, and then everything after it that fits
+        let mut visible_width = 0;
+        let mut start = text_field.cursor;
+        while start > 0 && visible_width + char_widths[start - 1] <= available_width {
+            start -= 1;
+            visible_width += char_widths[start];
+        }
+        let mut end = text_field.cursor;
+        while end < char_widths.len() && visible_width + char_widths[end] <= available_width {
+            visible_width += char_widths[end];
+            end += 1;
+        }
+
+        let x_at = |index: usize| {
+            let width: u32 = char_widths[start..index.clamp(start, end)].iter().sum();
+
+            rect.x() + margin + (width as f64 * scale) as i32
+        };
+        let text_height = (self.font.height().max(1) as f64 * scale) as u32;
+        let text_y = rect.center().y() - text_height as i32 / 2;
+
+        if let Some(selection) = text_field.selection() {
+            let (selection_x, selection_end_x) = (x_at(selection.start), x_at(selection.end));
+
+            if selection_end_x > selection_x {
+                canvas.set_draw_color(self.theme.ui_button_clicked);
+                Self::fill_rect(
+                    canvas,
+                    Rect::new(
+                        selection_x,
+                        text_y,
+                        (selection_end_x - selection_x) as u32,
+                        text_height,
+                    ),
+                )?;
+            }
+        }
+
+        if end > start {
+            let visible_text: String = text_field
+                .text
+                .chars()
+                .skip(start)
+                .take(end - start)
+                .collect();
+
+            let text_texture = Self::font_texture(
+                visible_text,
+                self.theme.ui_button_text,
+                &self.font,
+                &self.texture_creator,
+            )?;
+            let TextureQuery { width, height, .. } = text_texture.query();
+
+            Self::draw_texture(
+                canvas,
+                &text_texture,
+                None,
+                Rect::new(
+                    x_at(start),
+                    text_y,
+                    (width as f64 * scale) as u32,
+                    (height as f64 * scale) as u32,
+                ),
+            )?;
+        }
+
+        if text_field.is_focused {
+            canvas.set_draw_color(self.theme.ui_button_text);
+            Self::fill_rect(
+                canvas,
+                Rect::new(x_at(text_field.cursor), text_y, 2, text_height),
+            )?;
+        }
+
+        Ok(())
     }
 
-    fn draw_maze_visited_cells(
+    /// Covers the maze with a table of keys and what they do, one row each
+    fn draw_help(
         &self,
         canvas: &mut Canvas<impl RenderTarget>,
         layout: &WindowMazeLayout,
-        maze: &Maze,
+        help: &[(String, &str)],
     ) -> Result<(), DrawError> {
-        canvas.set_draw_color(VISITED_CELL_COLOR);
+        if help.is_empty() || layout.width == 0 || layout.height == 0 {
+            return Ok(());
+        }
 
-        for cell in maze.all_cells().filter(|&cell| maze.is_visited(cell)) {
-            let cell_x = layout.cell_x_positions[cell.x as usize];
-            let cell_y = layout.cell_y_positions[cell.y as usize];
-            let (cell_width, cell_height) =
-                layout.cell_size(Point::new(cell.x as usize, cell.y as usize));
+        let rect = Rect::new(
+            layout.position.x,
+            layout.position.y,
+            layout.width,
+            layout.height,
+        );
+
+        canvas.set_draw_color(self.theme.ui);
+        Self::fill_rect(canvas, rect)?;
+
+        let row_height = rect.height() / help.len() as u32;
+        let keys_width = rect.width() * 2 / 5;
+
+        for (row, (keys, description)) in help.iter().enumerate() {
+            let y = rect.y() + (row as u32 * row_height) as i32;
 
-            Self::fill_rect(canvas, Rect::new(cell_x, cell_y, cell_width, cell_height))?;
+            let keys_rect = Rect::new(rect.x(), y, keys_width, row_height);
+            let description_rect = Rect::new(
+                rect.x() + keys_width as i32,
+                y,
+                rect.width() - keys_width,
+                row_height,
+            );
+
+            self.draw_text(canvas, keys, keys_rect, false)?;
+            self.draw_text(canvas, description, description_rect, false)?;
         }
 
         Ok(())
     }
 
-    fn draw_maze_walls(
+    /// Draws lines of text in a box at the top left corner of the maze
+    pub fn draw_hud(
         &self,
         canvas: &mut Canvas<impl RenderTarget>,
         layout: &WindowMazeLayout,
-        maze: &Maze,
+        lines: &[String],
     ) -> Result<(), DrawError> {
-        let top_left = Point::new(layout.cell_x_positions[0], layout.cell_y_positions[0]);
-        let top_right = Point::new(
-            layout.cell_x_positions[maze.width() as usize],
-            layout.cell_y_positions[0],
-        );
-        let bottom_left = Point::new(
-            layout.cell_x_positions[0],
-            layout.cell_y_positions[maze.height() as usize],
-        );
-        let bottom_right = Point::new(
-            layout.cell_x_positions[maze.width() as usize],
-            layout.cell_y_positions[maze.height() as usize],
+        let viewport = canvas.viewport();
+
+        self.draw_text_box(canvas, layout.position.into(), lines, viewport)
+    }
+
+    /// Draws lines of text in a box at the top right corner of the maze
+    pub fn draw_notice(
+        &self,
+        canvas: &mut Canvas<impl RenderTarget>,
+        layout: &WindowMazeLayout,
+        lines: &[String],
+    ) -> Result<(), DrawError> {
+        let maze_rect = Rect::new(
+            layout.position.x,
+            layout.position.y,
+            layout.width,
+            layout.height,
         );
 
-        for (start, end) in [
-            (top_left, top_right),
-            (bottom_left, bottom_right),
-            (top_left, bottom_left),
-            (top_right, bottom_right),
+        self.draw_text_box(canvas, maze_rect.top_right(), lines, maze_rect)
+    }
+
+    /// Outlines a cell, and draws lines of text about it next to the mouse
+    pub fn draw_tooltip(
+        &self,
+        canvas: &mut Canvas<impl RenderTarget>,
+        layout: &WindowMazeLayout,
+        cell: MazePoint,
+        mouse: WindowPoint,
+        lines: &[String],
+    ) -> Result<(), DrawError> {
+        let rect = layout.cell_rect(Point::new(cell.x as usize, cell.y as usize));
+        let thickness = layout
+            .wall_thickness
+            .max(2)
+            .min(rect.width().min(rect.height()) / 2)
+            .max(1);
+
+        canvas.set_draw_color(self.theme.cursor);
+
+        for edge in [
+            Rect::new(rect.x(), rect.y(), rect.width(), thickness),
+            Rect::new(
+                rect.x(),
+                rect.bottom() - thickness as i32,
+                rect.width(),
+                thickness,
+            ),
+            Rect::new(rect.x(), rect.y(), thickness, rect.height()),
+            Rect::new(
+                rect.right() - thickness as i32,
+                rect.y(),
+                thickness,
+                rect.height(),
+            ),
         ] {
-            Self::draw_line(canvas, start, end, layout.wall_thickness, WALL_COLOR)?;
+            Self::fill_rect(canvas, edge)?;
         }
 
-        for wall in maze.walls() {
-            let is_horizontal = wall.first_cell().x != wall.second_cell().x;
+        let position = SdlPoint::from(mouse).offset(GFX_TOOLTIP_OFFSET, GFX_TOOLTIP_OFFSET);
+        let viewport = canvas.viewport();
 
-            let p1_maze =
-                Point::<Maze>::new(wall.second_cell().x as usize, wall.second_cell().y as usize);
-            let p2_maze = p1_maze + if is_horizontal { (0, 1) } else { (1, 0) };
-
-            let p1_window = Point::new(
-                layout.cell_x_positions[p1_maze.x],
-                layout.cell_y_positions[p1_maze.y],
-            );
-            let p2_window = Point::new(
-                layout.cell_x_positions[p2_maze.x],
-                layout.cell_y_positions[p2_maze.y],
-            );
+        self.draw_text_box(canvas, position, lines, viewport)
+    }
 
-            Self::draw_line(
+    /// Draws lines of text in a box with its top left corner at `position`,
+    /// moved back inside `bounds` if it would stick out
+    fn draw_text_box(
+        &self,
+        canvas: &mut Canvas<impl RenderTarget>,
+        position: SdlPoint,
+        lines: &[String],
+        bounds: Rect,
+    ) -> Result<(), DrawError> {
+        let textures = lines
+            .iter()
+            .map(|line| {
+                Self::font_texture(
+                    line,
+                    self.theme.ui_button_text,
+                    &self.font,
+                    &self.texture_creator,
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let scale = GFX_HUD_LINE_HEIGHT as f64 / self.font.height().max(1) as f64;
+        let margin = GFX_HUD_LINE_HEIGHT / 4;
+        let widest = textures
+            .iter()
+            .map(|texture| texture.query().width)
+            .max()
+            .unwrap_or(0);
+
+        let box_width = (widest as f64 * scale) as u32 + margin * 2;
+        let box_height = GFX_HUD_LINE_HEIGHT * lines.len() as u32 + margin * 2;
+
+        let x = position
+            .x()
+            .min(bounds.right() - box_width as i32)
+            .max(bounds.x());
+        let y = position
+            .y()
+            .min(bounds.bottom() - box_height as i32)
+            .max(bounds.y());
+
+        canvas.set_draw_color(self.theme.ui_button);
+        Self::fill_rect(canvas, Rect::new(x, y, box_width, box_height))?;
+
+        for (row, texture) in textures.iter().enumerate() {
+            let TextureQuery { width, height, .. } = texture.query();
+
+            Self::draw_texture(
                 canvas,
-                p1_window,
-                p2_window,
-                layout.wall_thickness,
-                WALL_COLOR,
+                texture,
+                None,
+                Rect::new(
+                    x + margin as i32,
+                    y + (margin + row as u32 * GFX_HUD_LINE_HEIGHT) as i32,
+                    (width as f64 * scale) as u32,
+                    (height as f64 * scale) as u32,
+                ),
             )?;
         }
 
         Ok(())
     }
 
-    fn draw_maze_cursor(
+    fn bounds_rect(bounds: Bounds) -> Rect {
+        Rect::new(
+            bounds.position.x as _,
+            bounds.position.y as _,
+            bounds.width as _,
+            bounds.height as _,
+        )
+    }
+
+    /// Widgets light up when hovered over, and darken while pressed
+    fn widget_color(&self, state: &ButtonState) -> Color {
+        match *state {
+            ButtonState::Normal { highlight, .. } => self
+                .theme
+                .ui_button
+                .blend(self.theme.ui_button_highlight, highlight),
+
+            ButtonState::Pressed => self.theme.ui_button_clicked,
+        }
+    }
+
+    /// Halfway between a button and the bar behind it, for widgets that can't be used
+    fn disabled_widget_color(&self) -> Color {
+        self.theme.ui_button.blend(self.theme.ui, 0.5)
+    }
+
+    /// Draws text inside a rectangle, shrunk to fit if needed, either centered
+    /// or on the left with a margin
+    fn draw_text(
         &self,
         canvas: &mut Canvas<impl RenderTarget>,
-        layout: &WindowMazeLayout,
-        maze: &Maze,
+        text: &str,
+        rect: Rect,
+        is_centered: bool,
     ) -> Result<(), DrawError> {
-        if let Some(cursor) = maze.cursor() {
-            let cell_x = layout.cell_x_positions[cursor.x as usize];
-            let cell_y = layout.cell_y_positions[cursor.y as usize];
-            let (cell_width, cell_height) =
-                layout.cell_size(Point::new(cursor.x as usize, cursor.y as usize));
+        if text.is_empty() {
+            return Ok(());
+        }
 
-            let cursor_rect = Rect::new(
-                cell_x + (cell_width as i32 / 4),
-                cell_y + (cell_height as i32 / 4),
-                cell_width / 2,
-                cell_height / 2,
-            );
+        let text_texture = Self::font_texture(
+            text,
+            self.theme.ui_button_text,
+            &self.font,
+            &self.texture_creator,
+        )?;
+
+        let TextureQuery {
+            width: text_width,
+            height: text_height,
+            ..
+        } = text_texture.query();
+
+        // The font has a fixed size, so the text is shrunk to fit smaller spaces
+        let scale = (rect.width() as f64 * TEXT_FIT / text_width as f64)
+            .min(rect.height() as f64 * TEXT_FIT / text_height as f64)
+            .min(1.0);
+
+        let mut text_rect = Rect::from_center(
+            rect.center(),
+            (text_width as f64 * scale) as u32,
+            (text_height as f64 * scale) as u32,
+        );
 
-            canvas.set_draw_color(CURSOR_COLOR);
-            Self::fill_rect(canvas, cursor_rect)?;
+        if !is_centered {
+            text_rect.set_x(rect.x() + (rect.width() as f64 * (1.0 - TEXT_FIT) / 2.0) as i32);
         }
 
-        Ok(())
+        Self::draw_texture(canvas, &text_texture, None, text_rect)
     }
 
-    // fn draw_ui(&self, canvas: &mut Canvas<impl RenderTarget>, ui: &Ui) -> Result<(), DrawError> {
-    //     let rect = Rect::new(
-    //         GFX_UI_X as _,
-    //         GFX_UI_Y as _,
-    //         GFX_UI_WIDTH as _,
-    //         GFX_UI_HEIGHT as _,
-    //     );
-    //     canvas.set_draw_color(UI_COLOR);
-    //     Self::fill_rect(canvas, rect)?;
-
-    //     for button in ui.buttons() {
-    //         let rect = Rect::new(
-    //             button.position.x as _,
-    //             button.position.y as _,
-    //             button.width as _,
-    //             button.height as _,
-    //         );
-
-    //         if let ButtonState::Normal { highlight, .. } = button.state {
-    //             canvas.set_draw_color(UI_BUTTON_COLOR.blend(UI_BUTTON_HIGHLIGHT_COLOR, highlight));
-    //         } else if let ButtonState::Pressed = button.state {
-    //             canvas.set_draw_color(UI_BUTTON_CLICKED_COLOR);
-    //         }
-
-    //         Self::fill_rect(canvas, rect)?;
-
-    //         let text = button.text();
-
-    //         let text_texture = Self::font_texture(
-    //             text.0,
-    //             UI_BUTTON_TEXT_COLOR,
-    //             &self.font,
-    //             &self.texture_creator,
-    //         )?;
-
-    //         let TextureQuery {
-    //             width: text_width,
-    //             height: text_height,
-    //             ..
-    //         } = text_texture.query();
-
-    //         let text_center = (
-    //             (button.position.x + button.width / 2) as _,
-    //             (button.position.y + button.height / 2) as _,
-    //         );
-    //         let text_rect = Rect::from_center(text_center, text_width, text_height);
-
-    //         Self::draw_texture(canvas, &text_texture, None, text_rect)?;
-    //     }
-
-    //     Ok(())
-    // }
-
     fn fill_rect(
         canvas: &mut Canvas<impl RenderTarget>,
         rect: impl Into<Option<Rect>>,
@@ -331,6 +678,204 @@ impl<'ttf> Graphics<'ttf> {
     }
 }
 
+/// Draws the maze itself, which doesn't need a font or a window,
+/// so it can also be drawn to an offscreen canvas
+pub struct MazeGraphics {
+    theme: Theme,
+}
+
+impl MazeGraphics {
+    pub fn new(theme: Theme) -> Self {
+        Self { theme }
+    }
+
+    /// Clears the canvas and draws the maze on it, as it's shown in the window
+    pub fn draw(
+        &self,
+        canvas: &mut Canvas<impl RenderTarget>,
+        layout: &WindowLayout,
+        maze: &Maze,
+        overlays: Overlays,
+    ) -> Result<(), DrawError> {
+        canvas.set_draw_color(self.theme.ui);
+        canvas.clear();
+
+        self.draw_maze(canvas, &layout.maze, maze, overlays)
+    }
+
+    fn draw_maze(
+        &self,
+        canvas: &mut Canvas<impl RenderTarget>,
+        layout: &WindowMazeLayout,
+        maze: &Maze,
+        overlays: Overlays,
+    ) -> Result<(), DrawError> {
+        // The overlays only make sense once the maze is done
+        let overlays = if maze.is_finished() {
+            overlays
+        } else {
+            Overlays::default()
+        };
+
+        self.draw_maze_background(canvas, layout, maze)?;
+
+        if overlays.heatmap {
+            self.draw_maze_heatmap(canvas, layout, maze)?;
+        } else {
+            self.draw_maze_visited_cells(canvas, layout, maze)?;
+        }
+
+        self.draw_maze_walls(canvas, layout, maze)?;
+
+        if overlays.solution {
+            self.draw_maze_solution(canvas, layout, maze, overlays.solver)?;
+        }
+
+        self.draw_maze_endpoints(canvas, layout, maze)?;
+        self.draw_maze_cursor(canvas, layout, maze)?;
+
+        Ok(())
+    }
+
+    fn draw_maze_background(
+        &self,
+        canvas: &mut Canvas<impl RenderTarget>,
+        layout: &WindowMazeLayout,
+        maze: &Maze,
+    ) -> Result<(), DrawError> {
+        canvas.set_draw_color(self.theme.background);
+
+        for cell in maze.all_cells() {
+            Graphics::fill_rect(
+                canvas,
+                layout.cell_rect(Point::new(cell.x as usize, cell.y as usize)),
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn draw_maze_visited_cells(
+        &self,
+        canvas: &mut Canvas<impl RenderTarget>,
+        layout: &WindowMazeLayout,
+        maze: &Maze,
+    ) -> Result<(), DrawError> {
+        canvas.set_draw_color(self.theme.visited_cell);
+
+        for cell in maze.all_cells().filter(|&cell| maze.is_visited(cell)) {
+            for rect in layout.cell_floor(maze, cell) {
+                Graphics::fill_rect(canvas, rect)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn draw_maze_heatmap(
+        &self,
+        canvas: &mut Canvas<impl RenderTarget>,
+        layout: &WindowMazeLayout,
+        maze: &Maze,
+    ) -> Result<(), DrawError> {
+        let distances = solve::distances(maze, maze.start());
+        let max_distance = distances.values().copied().max().unwrap_or(0).max(1);
+
+        for (&cell, &distance) in &distances {
+            canvas.set_draw_color(self.theme.heatmap(distance as f64 / max_distance as f64));
+
+            for rect in layout.cell_floor(maze, cell) {
+                Graphics::fill_rect(canvas, rect)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn draw_maze_walls(
+        &self,
+        canvas: &mut Canvas<impl RenderTarget>,
+        layout: &WindowMazeLayout,
+        maze: &Maze,
+    ) -> Result<(), DrawError> {
+        for (start, end) in layout.wall_lines(maze) {
+            Graphics::draw_line(canvas, start, end, layout.wall_thickness, self.theme.wall)?;
+        }
+
+        Ok(())
+    }
+
+    fn draw_maze_solution(
+        &self,
+        canvas: &mut Canvas<impl RenderTarget>,
+        layout: &WindowMazeLayout,
+        maze: &Maze,
+        solver: Solver,
+    ) -> Result<(), DrawError> {
+        let Some(solution) = solve::solve(maze, solver) else {
+            return Ok(());
+        };
+
+        let (_, marker_size) = layout.cell_center(maze.start());
+        let thickness = (marker_size / 2).max(1);
+
+        for pair in solution.windows(2) {
+            let (start, _) = layout.cell_center(pair[0]);
+            let (end, _) = layout.cell_center(pair[1]);
+
+            Graphics::draw_line(canvas, start, end, thickness, self.theme.solution)?;
+        }
+
+        Ok(())
+    }
+
+    fn draw_maze_endpoints(
+        &self,
+        canvas: &mut Canvas<impl RenderTarget>,
+        layout: &WindowMazeLayout,
+        maze: &Maze,
+    ) -> Result<(), DrawError> {
+        for (cell, color) in [
+            (maze.start(), self.theme.start),
+            (maze.goal(), self.theme.goal),
+        ] {
+            let (center, size) = layout.cell_center(cell);
+
+            canvas
+                .filled_circle(center.x as i16, center.y as i16, (size / 2) as i16, color)
+                .map_err(DrawError::DrawCircle)?;
+        }
+
+        Ok(())
+    }
+
+    fn draw_maze_cursor(
+        &self,
+        canvas: &mut Canvas<impl RenderTarget>,
+        layout: &WindowMazeLayout,
+        maze: &Maze,
+    ) -> Result<(), DrawError> {
+        if let Some(cursor) = maze.cursor() {
+            let cell_x = layout.cell_x_positions[cursor.x as usize];
+            let cell_y = layout.cell_y_positions[cursor.y as usize];
+            let (cell_width, cell_height) =
+                layout.cell_size(Point::new(cursor.x as usize, cursor.y as usize));
+
+            let cursor_rect = Rect::new(
+                cell_x + (cell_width as i32 / 4),
+                cell_y + (cell_height as i32 / 4),
+                cell_width / 2,
+                cell_height / 2,
+            );
+
+            canvas.set_draw_color(self.theme.cursor);
+            Graphics::fill_rect(canvas, cursor_rect)?;
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Debug)]
 pub struct FontLoadError(String);
 
@@ -358,6 +903,7 @@ impl From<String> for FontLoadError {
 pub enum DrawError {
     FillRect(String),
     DrawLine(String),
+    DrawCircle(String),
     LineTooThick(u32, TryFromIntError),
     DrawTexture(String),
     FontRendering(FontError),
@@ -371,7 +917,9 @@ impl Error for DrawError {
             Self::FontRendering(e) => Some(e),
             Self::SurfaceToTexture(e) => Some(e),
 
-            Self::FillRect(_) | Self::DrawLine(_) | Self::DrawTexture(_) => None,
+            Self::FillRect(_) | Self::DrawLine(_) | Self::DrawCircle(_) | Self::DrawTexture(_) => {
+                None
+            }
         }
     }
 }
@@ -381,6 +929,7 @@ impl Display for DrawError {
         match self {
             Self::FillRect(e) => write!(f, "Failed to fill rect: {}", e),
             Self::DrawLine(e) => write!(f, "Failed to draw line: {}", e),
+            Self::DrawCircle(e) => write!(f, "Failed to draw circle: {}", e),
             Self::LineTooThick(thickness, e) => write!(
                 f,
                 "Attempted to draw line with thickness of {}, but only up to 255 is supported ({})",
@@ -0,0 +1,31 @@
+use std::time::Duration;
+
+use maze_gen::maze::Maze;
+
+/// How long the maze's generation has taken, timed by the app as it steps the maze
+#[derive(Clone, Copy, Default)]
+pub struct GenerationStats {
+    /// Time spent taking generation steps, by hand or auto-stepping, not counting
+    /// the time in between them
+    pub elapsed: Duration,
+}
+
+/// The lines of the HUD, from top to bottom
+pub fn lines(current_fps: usize, stats: GenerationStats, maze: &Maze, seed: u64) -> Vec<String> {
+    let visited = maze.visited_count();
+    let total = maze.cell_count();
+    // Rounded down, so the maze isn't shown as done until it is
+    let percentage = visited * 100 / total.max(1);
+
+    vec![
+        format!("FPS: {}", current_fps),
+        format!("Steps: {}", maze.total_steps()),
+        format!("Visited: {}/{} ({}%)", visited, total, percentage),
+        format!("Algorithm: {}", maze.algorithm().name()),
+        format!("Seed: {}", seed),
+        format!(
+            "Generation time: {:.1}ms",
+            stats.elapsed.as_secs_f64() * 1000.0
+        ),
+    ]
+}
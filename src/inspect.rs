@@ -0,0 +1,41 @@
+use maze_gen::maze::Maze;
+use maze_gen::point::MazePoint;
+use maze_gen::solve;
+
+use crate::analysis::Analysis;
+
+/// The lines of a cell's tooltip, from top to bottom
+pub fn cell_lines(maze: &Maze, cell: MazePoint, analysis: &Analysis) -> Vec<String> {
+    let open_directions: Vec<&str> = solve::open_directions(maze, cell)
+        .into_iter()
+        .map(direction_name)
+        .collect();
+    let open_directions = if open_directions.is_empty() {
+        "none".to_string()
+    } else {
+        open_directions.join(", ")
+    };
+
+    let distance = analysis
+        .distances()
+        .get(&cell)
+        .map_or("unreachable".to_string(), |distance| distance.to_string());
+
+    let is_on_solution = analysis.solution().is_some_and(|path| path.contains(&cell));
+
+    vec![
+        format!("Cell: ({}, {})", cell.x, cell.y),
+        format!("Open: {}", open_directions),
+        format!("Distance from start: {}", distance),
+        format!("On solution: {}", if is_on_solution { "yes" } else { "no" }),
+    ]
+}
+
+fn direction_name(dir: (i64, i64)) -> &'static str {
+    match dir {
+        (-1, 0) => "west",
+        (1, 0) => "east",
+        (0, -1) => "north",
+        _ => "south",
+    }
+}
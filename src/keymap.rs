@@ -0,0 +1,378 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use sdl2::keyboard::Scancode;
+
+/// Something the app can do from the keyboard
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    Step,
+    StepBack,
+    AutoStepOrPause,
+    Finish,
+    Reset,
+    ToggleSolution,
+    ToggleHeatmap,
+    CycleAlgorithm,
+    Wider,
+    Narrower,
+    Taller,
+    Shorter,
+    KeepSquare,
+    Faster,
+    Slower,
+    ExportPng,
+    SaveMaze,
+    CopyCode,
+    PasteCode,
+    ToggleFullscreen,
+    ToggleSettings,
+    ToggleEditing,
+    Undo,
+    Redo,
+    ToggleHud,
+    ToggleHelp,
+}
+
+impl Action {
+    pub fn all() -> &'static [Action] {
+        &[
+            Action::Quit,
+            Action::Step,
+            Action::StepBack,
+            Action::AutoStepOrPause,
+            Action::Finish,
+            Action::Reset,
+            Action::ToggleSolution,
+            Action::ToggleHeatmap,
+            Action::CycleAlgorithm,
+            Action::Wider,
+            Action::Narrower,
+            Action::Taller,
+            Action::Shorter,
+            Action::KeepSquare,
+            Action::Faster,
+            Action::Slower,
+            Action::ExportPng,
+            Action::SaveMaze,
+            Action::CopyCode,
+            Action::PasteCode,
+            Action::ToggleFullscreen,
+            Action::ToggleSettings,
+            Action::ToggleEditing,
+            Action::Undo,
+            Action::Redo,
+            Action::ToggleHud,
+            Action::ToggleHelp,
+        ]
+    }
+
+    /// The name used in key map files
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Quit => "quit",
+            Action::Step => "step",
+            Action::StepBack => "step-back",
+            Action::AutoStepOrPause => "auto-step",
+            Action::Finish => "finish",
+            Action::Reset => "reset",
+            Action::ToggleSolution => "solution",
+            Action::ToggleHeatmap => "heatmap",
+            Action::CycleAlgorithm => "algorithm",
+            Action::Wider => "wider",
+            Action::Narrower => "narrower",
+            Action::Taller => "taller",
+            Action::Shorter => "shorter",
+            Action::KeepSquare => "keep-square",
+            Action::Faster => "faster",
+            Action::Slower => "slower",
+            Action::ExportPng => "export",
+            Action::SaveMaze => "save",
+            Action::CopyCode => "copy-code",
+            Action::PasteCode => "paste-code",
+            Action::ToggleFullscreen => "fullscreen",
+            Action::ToggleSettings => "settings",
+            Action::ToggleEditing => "edit",
+            Action::Undo => "undo",
+            Action::Redo => "redo",
+            Action::ToggleHud => "hud",
+            Action::ToggleHelp => "help",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all()
+            .iter()
+            .find(|action| action.name() == name)
+            .copied()
+    }
+
+    /// What the action does, as shown in the help overlay
+    pub fn description(&self) -> &'static str {
+        match self {
+            Action::Quit => "Quit",
+            Action::Step => "Take a generation step",
+            Action::StepBack => "Undo a generation step",
+            Action::AutoStepOrPause => "Auto-step or pause",
+            Action::Finish => "Finish generating",
+            Action::Reset => "Start generating over",
+            Action::ToggleSolution => "Show or hide the solution",
+            Action::ToggleHeatmap => "Show or hide the heatmap",
+            Action::CycleAlgorithm => "Switch to the next algorithm",
+            Action::Wider => "Make the maze wider",
+            Action::Narrower => "Make the maze narrower",
+            Action::Taller => "Make the maze taller",
+            Action::Shorter => "Make the maze shorter",
+            Action::KeepSquare => "Keep the maze square",
+            Action::Faster => "Generate faster",
+            Action::Slower => "Generate slower",
+            Action::ExportPng => "Export a PNG image",
+            Action::SaveMaze => "Save the maze",
+            Action::CopyCode => "Copy a share code",
+            Action::PasteCode => "Paste a share code",
+            Action::ToggleFullscreen => "Toggle fullscreen",
+            Action::ToggleSettings => "Show or hide the settings",
+            Action::ToggleEditing => "Edit the maze with the mouse, or stop",
+            Action::Undo => "Undo an edit",
+            Action::Redo => "Redo an edit",
+            Action::ToggleHud => "Show or hide generation stats",
+            Action::ToggleHelp => "Show or hide this help",
+        }
+    }
+
+    /// Whether holding down the key keeps doing the action, rather than toggling it back and forth
+    pub fn is_repeatable(&self) -> bool {
+        matches!(
+            self,
+            Action::Step
+                | Action::StepBack
+                | Action::Wider
+                | Action::Narrower
+                | Action::Taller
+                | Action::Shorter
+                | Action::Faster
+                | Action::Slower
+                | Action::Undo
+                | Action::Redo
+        )
+    }
+}
+
+/// Which keys do which actions, where an action can have any number of keys
+pub struct KeyMap {
+    bindings: Vec<(Scancode, Action)>,
+}
+
+impl KeyMap {
+    /// Loads a key map file, which rebinds some of the actions and leaves the rest as they are
+    ///
+    /// Every line binds a key to an action, as `action = key` with SDL's key names,
+    /// like `step = Space` or `faster = Keypad +`. The first line for an action replaces
+    /// its default keys, and further lines add more keys. Empty lines and lines starting
+    /// with `#` are ignored.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, KeyMapError> {
+        Self::parse(&fs::read_to_string(path).map_err(KeyMapError::Io)?)
+    }
+
+    /// Parses the text of a key map file, as described in [`KeyMap::load`]
+    pub fn parse(text: &str) -> Result<Self, KeyMapError> {
+        let mut key_map = Self::default();
+        let mut rebound_actions = Vec::new();
+
+        for (index, line) in text.lines().enumerate() {
+            let line_number = index + 1;
+            let line = line.trim();
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (action_name, key_name) = line
+                .split_once('=')
+                .ok_or(KeyMapError::MissingEquals(line_number))?;
+            let (action_name, key_name) = (action_name.trim(), key_name.trim());
+
+            let action = Action::from_name(action_name)
+                .ok_or_else(|| KeyMapError::UnknownAction(line_number, action_name.to_string()))?;
+            let scancode = Scancode::from_name(key_name)
+                .ok_or_else(|| KeyMapError::UnknownKey(line_number, key_name.to_string()))?;
+
+            if !rebound_actions.contains(&action) {
+                key_map
+                    .bindings
+                    .retain(|&(_, bound_action)| bound_action != action);
+                rebound_actions.push(action);
+            }
+
+            key_map.bind(scancode, action);
+        }
+
+        Ok(key_map)
+    }
+
+    /// Binds a key to an action, instead of whatever it was bound to before
+    pub fn bind(&mut self, scancode: Scancode, action: Action) {
+        self.bindings
+            .retain(|&(bound_scancode, _)| bound_scancode != scancode);
+        self.bindings.push((scancode, action));
+    }
+
+    pub fn action(&self, scancode: Scancode) -> Option<Action> {
+        self.bindings
+            .iter()
+            .find(|&&(bound_scancode, _)| bound_scancode == scancode)
+            .map(|&(_, action)| action)
+    }
+
+    pub fn keys(&self, action: Action) -> impl Iterator<Item = Scancode> + '_ {
+        self.bindings
+            .iter()
+            .filter(move |&&(_, bound_action)| bound_action == action)
+            .map(|&(scancode, _)| scancode)
+    }
+
+    /// The keys of every action that has any, next to what the action does
+    pub fn help(&self) -> Vec<(String, &'static str)> {
+        Action::all()
+            .iter()
+            .filter_map(|&action| {
+                let keys: Vec<&str> = self.keys(action).map(|scancode| scancode.name()).collect();
+
+                (!keys.is_empty()).then(|| (keys.join(" / "), action.description()))
+            })
+            .collect()
+    }
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        let bindings = vec![
+            (Scancode::Escape, Action::Quit),
+            (Scancode::Space, Action::Step),
+            (Scancode::Backspace, Action::StepBack),
+            (Scancode::Return, Action::AutoStepOrPause),
+            (Scancode::F, Action::Finish),
+            (Scancode::R, Action::Reset),
+            (Scancode::L, Action::ToggleSolution),
+            (Scancode::H, Action::ToggleHeatmap),
+            (Scancode::A, Action::CycleAlgorithm),
+            (Scancode::Right, Action::Wider),
+            (Scancode::Left, Action::Narrower),
+            (Scancode::Up, Action::Taller),
+            (Scancode::Down, Action::Shorter),
+            (Scancode::K, Action::KeepSquare),
+            (Scancode::Equals, Action::Faster),
+            (Scancode::KpPlus, Action::Faster),
+            (Scancode::Minus, Action::Slower),
+            (Scancode::KpMinus, Action::Slower),
+            (Scancode::P, Action::ExportPng),
+            (Scancode::S, Action::SaveMaze),
+            (Scancode::C, Action::CopyCode),
+            (Scancode::V, Action::PasteCode),
+            (Scancode::F11, Action::ToggleFullscreen),
+            (Scancode::Tab, Action::ToggleSettings),
+            (Scancode::E, Action::ToggleEditing),
+            (Scancode::Z, Action::Undo),
+            (Scancode::Y, Action::Redo),
+            (Scancode::F3, Action::ToggleHud),
+            // The key with `?` on it, on US keyboards
+            (Scancode::Slash, Action::ToggleHelp),
+            (Scancode::F1, Action::ToggleHelp),
+        ];
+
+        Self { bindings }
+    }
+}
+
+#[derive(Debug)]
+pub enum KeyMapError {
+    Io(io::Error),
+    MissingEquals(usize),
+    UnknownAction(usize, String),
+    UnknownKey(usize, String),
+}
+
+impl Display for KeyMapError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Failed to read key map: {}", e),
+            Self::MissingEquals(line) => {
+                write!(f, "Line {}: expected `action = key`", line)
+            }
+            Self::UnknownAction(line, name) => {
+                write!(f, "Line {}: unknown action '{}'", line, name)
+            }
+            Self::UnknownKey(line, name) => write!(f, "Line {}: unknown key '{}'", line, name),
+        }
+    }
+}
+
+impl Error for KeyMapError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(e) => Some(e),
+
+            Self::MissingEquals(_) | Self::UnknownAction(..) | Self::UnknownKey(..) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comments_and_blank_lines_are_ignored() {
+        let key_map = KeyMap::parse("# Generation\n\n   \n  # indented\nstep = X\n").unwrap();
+
+        assert_eq!(
+            key_map.keys(Action::Step).collect::<Vec<_>>(),
+            [Scancode::X]
+        );
+    }
+
+    #[test]
+    fn rebinding_replaces_only_that_actions_default_keys() {
+        let key_map = KeyMap::parse("step = X\nstep = Return").unwrap();
+
+        assert_eq!(
+            key_map.keys(Action::Step).collect::<Vec<_>>(),
+            [Scancode::X, Scancode::Return]
+        );
+        assert!(key_map.action(Scancode::Space).is_none());
+        assert!(key_map.action(Scancode::Return) == Some(Action::Step));
+        assert!(key_map.action(Scancode::F) == Some(Action::Finish));
+    }
+
+    #[test]
+    fn a_key_bound_twice_does_the_last_action() {
+        let key_map = KeyMap::parse("step = X\nstep = X\nfinish = X").unwrap();
+
+        assert_eq!(key_map.keys(Action::Step).count(), 0);
+        assert_eq!(
+            key_map.keys(Action::Finish).collect::<Vec<_>>(),
+            [Scancode::X]
+        );
+        assert!(key_map.action(Scancode::X) == Some(Action::Finish));
+    }
+
+    #[test]
+    fn unknown_names_are_errors_with_their_line() {
+        assert!(matches!(
+            KeyMap::parse("step = X\n\njump = Space"),
+            Err(KeyMapError::UnknownAction(3, name)) if name == "jump"
+        ));
+        assert!(matches!(
+            KeyMap::parse("# keys\nstep = Nowhere"),
+            Err(KeyMapError::UnknownKey(2, name)) if name == "Nowhere"
+        ));
+        assert!(matches!(
+            KeyMap::parse("step Space"),
+            Err(KeyMapError::MissingEquals(1))
+        ));
+    }
+}
@@ -1,7 +1,11 @@
+use sdl2::rect::Rect;
 use sdl2::video::Window;
 
-use crate::maze::Maze;
+use maze_gen::maze::{Maze, Orientation, Topology};
+use maze_gen::point::MazePoint;
+
 use crate::point_new::Point;
+use crate::window_point::{Convert, WindowPoint};
 
 #[derive(Clone, Copy)]
 pub struct LayoutConfig {
@@ -9,10 +13,26 @@ pub struct LayoutConfig {
 
     pub maze_width: f64,
     pub maze_height: f64,
+    /// Shrink the maze area to the aspect ratio of the maze's columns and rows,
+    /// so that its cells are square instead of stretched to fill `maze_width` by `maze_height`
+    pub maze_square_cells: bool,
     pub maze_wall_thickness: f64,
+    /// How far the corridors of a weave maze are inset from the cell edges, as a fraction of a cell
+    pub maze_weave_inset: f64,
+
+    /// The height of the button bar along the bottom of the window,
+    /// as a fraction of the window's shorter side
+    pub ui_height: f64,
+    /// The space around every widget, as a fraction of the button bar's height
+    pub ui_padding: f64,
+    /// The width of the settings panel along the right of the window,
+    /// as a fraction of the window's shorter side
+    pub panel_width: f64,
 }
 
 pub struct Layout {
+    config: LayoutConfig,
+
     total_width: f64,
     total_height: f64,
 
@@ -23,11 +43,15 @@ pub struct Layout {
 
 impl Layout {
     pub fn new(config: LayoutConfig) -> Self {
+        Self::with_maze_size(config, config.maze_width, config.maze_height)
+    }
+
+    fn with_maze_size(config: LayoutConfig, maze_width: f64, maze_height: f64) -> Self {
         let LayoutConfig {
             padding,
-            maze_width,
-            maze_height,
             maze_wall_thickness,
+            maze_weave_inset,
+            ..
         } = config;
 
         let total_width = maze_width + padding * 2.0;
@@ -38,9 +62,11 @@ impl Layout {
             maze_width,
             maze_height,
             maze_wall_thickness,
+            maze_weave_inset,
         );
 
         Self {
+            config,
             total_width,
             total_height,
             padding,
@@ -55,16 +81,81 @@ impl Layout {
         maze_cols: usize,
         maze_rows: usize,
     ) -> WindowLayout {
-        WindowLayout::new(self, window_width, window_height, maze_cols, maze_rows)
+        let layout = self.fit_to_grid(maze_cols, maze_rows);
+
+        WindowLayout::new(&layout, window_width, window_height, maze_cols, maze_rows)
+    }
+
+    /// The same layout without the button bar or the settings panel, for images of just the maze
+    pub fn without_ui(&self) -> Self {
+        Self::new(LayoutConfig {
+            ui_height: 0.0,
+            panel_width: 0.0,
+            ..self.config
+        })
+    }
+
+    /// The same layout with the settings panel collapsed, leaving its space to the maze
+    pub fn without_panel(&self) -> Self {
+        Self::new(LayoutConfig {
+            panel_width: 0.0,
+            ..self.config
+        })
     }
 
     pub fn aspect_ratio(&self) -> f64 {
         self.total_width / self.total_height
     }
+
+    /// The size of an image that fits the layout for a maze with the given columns and rows
+    /// exactly, without letterboxing
+    pub fn image_size(&self, longest_side: u32, maze_cols: usize, maze_rows: usize) -> (u32, u32) {
+        let aspect_ratio = self.fit_to_grid(maze_cols, maze_rows).aspect_ratio();
+
+        if aspect_ratio >= 1.0 {
+            (
+                longest_side,
+                (longest_side as f64 / aspect_ratio).round() as u32,
+            )
+        } else {
+            (
+                (longest_side as f64 * aspect_ratio).round() as u32,
+                longest_side,
+            )
+        }
+    }
+
+    /// The layout for a maze with the given columns and rows
+    ///
+    /// With square cells, the maze area is the largest one with the grid's aspect ratio
+    /// that fits in the configured maze area, and the whole layout's aspect ratio changes with it,
+    /// so the window letterboxes it instead of stretching the cells.
+    fn fit_to_grid(&self, maze_cols: usize, maze_rows: usize) -> Self {
+        let LayoutConfig {
+            maze_width,
+            maze_height,
+            maze_square_cells,
+            ..
+        } = self.config;
+
+        if !maze_square_cells {
+            return Self::new(self.config);
+        }
+
+        let cell_size = (maze_width / maze_cols as f64).min(maze_height / maze_rows as f64);
+
+        Self::with_maze_size(
+            self.config,
+            cell_size * maze_cols as f64,
+            cell_size * maze_rows as f64,
+        )
+    }
 }
 
 pub struct WindowLayout {
     pub maze: WindowMazeLayout,
+    pub ui: WindowUiLayout,
+    pub panel: WindowUiLayout,
 }
 
 impl WindowLayout {
@@ -75,6 +166,33 @@ impl WindowLayout {
         maze_cols: usize,
         maze_rows: usize,
     ) -> Self {
+        // The button bar takes the bottom of the window and the settings panel the right of
+        // what's left, and the maze is fit into the rest
+        let shorter_side = window_width.min(window_height) as f64;
+
+        let ui_height =
+            ((layout.config.ui_height * shorter_side) as u32).min(window_height.saturating_sub(1));
+        let padding = (ui_height as f64 * layout.config.ui_padding) as u32;
+        let ui = WindowUiLayout {
+            position: Point::new(0, (window_height - ui_height) as i32),
+            width: window_width,
+            height: ui_height,
+            padding,
+        };
+
+        let window_height = window_height - ui_height;
+
+        let panel_width =
+            ((layout.config.panel_width * shorter_side) as u32).min(window_width.saturating_sub(1));
+        let panel = WindowUiLayout {
+            position: Point::new((window_width - panel_width) as i32, 0),
+            width: panel_width,
+            height: window_height,
+            padding,
+        };
+
+        let window_width = window_width - panel_width;
+
         let layout_aspect_ratio = layout.aspect_ratio();
         let window_aspect_ratio = window_width as f64 / window_height as f64;
 
@@ -112,7 +230,7 @@ impl WindowLayout {
             maze_rows,
         );
 
-        Self { maze }
+        Self { maze, ui, panel }
     }
 }
 
@@ -121,6 +239,7 @@ pub struct WindowMazeLayout {
     pub width: u32,
     pub height: u32,
     pub wall_thickness: u32,
+    pub weave_inset: f64,
 
     pub cell_x_positions: Vec<i32>,
     pub cell_y_positions: Vec<i32>,
@@ -169,6 +288,7 @@ impl WindowMazeLayout {
             width: width as _,
             height: height as _,
             wall_thickness,
+            weave_inset: layout.maze.weave_inset,
             cell_x_positions,
             cell_y_positions,
         }
@@ -180,6 +300,242 @@ impl WindowMazeLayout {
 
         (width, height)
     }
+
+    /// The x and y positions of a weave maze cell's edges and of its inset corridor,
+    /// in increasing order: `[edge, inset, inset, edge]`
+    pub fn weave_cell_bounds(&self, cell: Point<Maze>) -> ([i32; 4], [i32; 4]) {
+        let (width, height) = self.cell_size(cell);
+
+        let x_inset = (width as f64 * self.weave_inset) as i32;
+        let y_inset = (height as f64 * self.weave_inset) as i32;
+
+        let x0 = self.cell_x_positions[cell.x];
+        let y0 = self.cell_y_positions[cell.y];
+        let x3 = self.cell_x_positions[cell.x + 1];
+        let y3 = self.cell_y_positions[cell.y + 1];
+
+        (
+            [x0, x0 + x_inset, x3 - x_inset, x3],
+            [y0, y0 + y_inset, y3 - y_inset, y3],
+        )
+    }
+
+    /// The cell under a position in the window, if it's over the maze's grid
+    pub fn cell_at(&self, point: WindowPoint) -> Option<MazePoint> {
+        let (&left, &right) = (
+            self.cell_x_positions.first()?,
+            self.cell_x_positions.last()?,
+        );
+        let (&top, &bottom) = (
+            self.cell_y_positions.first()?,
+            self.cell_y_positions.last()?,
+        );
+
+        let is_inside = (left as i64..right as i64).contains(&point.x)
+            && (top as i64..bottom as i64).contains(&point.y);
+
+        is_inside.then(|| point.convert(self))
+    }
+
+    pub fn cell_rect(&self, cell: Point<Maze>) -> Rect {
+        let (width, height) = self.cell_size(cell);
+
+        Rect::new(
+            self.cell_x_positions[cell.x],
+            self.cell_y_positions[cell.y],
+            width,
+            height,
+        )
+    }
+
+    /// The center of a cell, and the size of a marker that fits inside it
+    pub fn cell_center(&self, cell: MazePoint) -> (Point<Window>, u32) {
+        let rect = self.cell_rect(Point::new(cell.x as usize, cell.y as usize));
+
+        (rect.center().into(), rect.width().min(rect.height()) / 2)
+    }
+
+    /// The parts of a cell that a path goes through: the whole cell in an orthogonal maze,
+    /// or the inset corridor and its openings to the neighbouring cells in a weave maze
+    pub fn cell_floor(&self, maze: &Maze, cell: MazePoint) -> Vec<Rect> {
+        let maze_cell = Point::new(cell.x as usize, cell.y as usize);
+
+        if maze.topology() == Topology::Orthogonal {
+            return vec![self.cell_rect(maze_cell)];
+        }
+
+        let ([x0, x1, x2, x3], [y0, y1, y2, y3]) = self.weave_cell_bounds(maze_cell);
+
+        let mut rects = vec![(x1, y1, x2, y2)];
+
+        for (dir, rect) in [
+            ((0, -1), (x1, y0, x2, y1)),
+            ((0, 1), (x1, y2, x2, y3)),
+            ((-1, 0), (x0, y1, x1, y2)),
+            ((1, 0), (x2, y1, x3, y2)),
+        ] {
+            if maze.is_passage(cell, cell + dir) {
+                rects.push(rect);
+            }
+        }
+
+        rects
+            .into_iter()
+            .map(|(left, top, right, bottom)| {
+                Rect::new(left, top, (right - left) as u32, (bottom - top) as u32)
+            })
+            .collect()
+    }
+
+    /// Every wall of the maze, as the start and end of a line `wall_thickness` thick
+    pub fn wall_lines(&self, maze: &Maze) -> Vec<(Point<Window>, Point<Window>)> {
+        match maze.topology() {
+            Topology::Orthogonal => self.orthogonal_wall_lines(maze),
+            Topology::Weave => self.weave_wall_lines(maze),
+        }
+    }
+
+    fn orthogonal_wall_lines(&self, maze: &Maze) -> Vec<(Point<Window>, Point<Window>)> {
+        let mut lines = vec![];
+
+        // The outer boundary follows the edge of the mask,
+        // so it's drawn wherever an enabled cell borders a disabled cell or the edge of the grid
+        for cell in maze.all_cells() {
+            let top_left = Point::<Maze>::new(cell.x as usize, cell.y as usize);
+            let top_right = top_left + (1, 0);
+            let bottom_left = top_left + (0, 1);
+            let bottom_right = top_left + (1, 1);
+
+            for (neighbour, start, end) in [
+                (cell + (0, -1), top_left, top_right),
+                (cell + (0, 1), bottom_left, bottom_right),
+                (cell + (-1, 0), top_left, bottom_left),
+                (cell + (1, 0), top_right, bottom_right),
+            ] {
+                if !maze.is_point_inside(neighbour) {
+                    lines.push((self.corner(start), self.corner(end)));
+                }
+            }
+        }
+
+        for wall in maze.walls() {
+            let is_horizontal = wall.first_cell().x != wall.second_cell().x;
+
+            let start =
+                Point::<Maze>::new(wall.second_cell().x as usize, wall.second_cell().y as usize);
+            let end = start + if is_horizontal { (0, 1) } else { (1, 0) };
+
+            lines.push((self.corner(start), self.corner(end)));
+        }
+
+        lines
+    }
+
+    /// The walls of every corridor of a weave maze, inset from the cell edges
+    ///
+    /// A cell with a passage tunnelling beneath it is drawn as its over-passage,
+    /// with the ends of the under-passage showing as gaps in the over-passage's side walls.
+    fn weave_wall_lines(&self, maze: &Maze) -> Vec<(Point<Window>, Point<Window>)> {
+        let mut lines = vec![];
+
+        for cell in maze.all_cells() {
+            let ([x0, x1, x2, x3], [y0, y1, y2, y3]) =
+                self.weave_cell_bounds(Point::new(cell.x as usize, cell.y as usize));
+
+            let under_passage = maze.under_passage(cell);
+
+            for (dir, orientation, corridor_sides, inset_edge) in [
+                (
+                    (0, -1),
+                    Orientation::Vertical,
+                    [((x1, y0), (x1, y1)), ((x2, y0), (x2, y1))],
+                    ((x1, y1), (x2, y1)),
+                ),
+                (
+                    (0, 1),
+                    Orientation::Vertical,
+                    [((x1, y2), (x1, y3)), ((x2, y2), (x2, y3))],
+                    ((x1, y2), (x2, y2)),
+                ),
+                (
+                    (-1, 0),
+                    Orientation::Horizontal,
+                    [((x0, y1), (x1, y1)), ((x0, y2), (x1, y2))],
+                    ((x1, y1), (x1, y2)),
+                ),
+                (
+                    (1, 0),
+                    Orientation::Horizontal,
+                    [((x2, y1), (x3, y1)), ((x2, y2), (x3, y2))],
+                    ((x2, y1), (x2, y2)),
+                ),
+            ] {
+                let is_passage = maze.is_passage(cell, cell + dir);
+                let is_under_passage = under_passage == Some(orientation);
+
+                if is_passage {
+                    for (start, end) in corridor_sides {
+                        lines.push((start.into(), end.into()));
+                    }
+                }
+
+                if !is_passage || is_under_passage {
+                    let (start, end) = inset_edge;
+
+                    lines.push((start.into(), end.into()));
+                }
+            }
+        }
+
+        lines
+    }
+
+    /// The window position of the corner at the top left of a cell
+    fn corner(&self, cell: Point<Maze>) -> Point<Window> {
+        Point::new(self.cell_x_positions[cell.x], self.cell_y_positions[cell.y])
+    }
+}
+
+/// An area of the window for widgets, like the button bar or the settings panel
+pub struct WindowUiLayout {
+    pub position: Point<Window>,
+    pub width: u32,
+    pub height: u32,
+    pub padding: u32,
+}
+
+impl WindowUiLayout {
+    /// The whole area, or `None` if it's hidden
+    pub fn rect(&self) -> Option<Rect> {
+        (self.width > 0 && self.height > 0)
+            .then(|| Rect::new(self.position.x, self.position.y, self.width, self.height))
+    }
+
+    /// Evenly sized widgets in a number of rows, left to right and then top to bottom,
+    /// with padding around and between them
+    ///
+    /// There are as many columns as it takes to fit every widget, and rows may be left empty.
+    pub fn widget_rects(&self, count: usize, rows: usize) -> Vec<Rect> {
+        let rows = rows.max(1) as i32;
+        let cols = ((count as i32 + rows - 1) / rows).max(1);
+        let padding = self.padding as i32;
+
+        let widget_width = ((self.width as i32 - padding * (cols + 1)) / cols).max(1);
+        let widget_height = ((self.height as i32 - padding * (rows + 1)) / rows).max(1);
+
+        (0..count as i32)
+            .map(|i| {
+                let (col, row) = (i % cols, i / cols);
+
+                Rect::new(
+                    self.position.x + padding + (widget_width + padding) * col,
+                    self.position.y + padding + (widget_height + padding) * row,
+                    widget_width as u32,
+                    widget_height as u32,
+                )
+            })
+            .collect()
+    }
 }
 
 struct MazeLayout {
@@ -187,15 +543,23 @@ struct MazeLayout {
     width: f64,
     height: f64,
     wall_thickness: f64,
+    weave_inset: f64,
 }
 
 impl MazeLayout {
-    fn new(position: Point<Layout>, width: f64, height: f64, wall_thickness: f64) -> Self {
+    fn new(
+        position: Point<Layout>,
+        width: f64,
+        height: f64,
+        wall_thickness: f64,
+        weave_inset: f64,
+    ) -> Self {
         Self {
             position,
             width,
             height,
             wall_thickness,
+            weave_inset,
         }
     }
 }
@@ -1,69 +1,269 @@
+mod analysis;
 mod app;
+mod cli;
+mod clipboard;
 mod color_blend;
 mod color_hex;
 mod context;
+mod editor;
+mod export;
 mod graphics;
+mod hud;
+mod inspect;
+mod keymap;
 mod layout;
-mod maze;
-mod point;
+mod overlays;
 mod point_new;
+mod speed;
 mod stable_loop;
+mod svg;
+mod theme;
 mod ui;
+mod window_point;
 
-use sdl2::pixels::Color;
+use std::env;
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use std::process;
 
-use crate::app::App;
-use crate::color_hex::hex_rgb;
+use maze_gen::image::Image;
+use maze_gen::import::{self, Grid, ImportError};
+use maze_gen::mask::Mask;
+use maze_gen::maze::{Algorithm, Maze, Topology};
+use maze_gen::save::{self, SavedMaze};
+use maze_gen::share;
+
+use crate::app::{App, MazeSettings, ViewSettings};
+use crate::cli::Args;
 use crate::context::Context;
+use crate::export::Recording;
+use crate::keymap::KeyMap;
 use crate::layout::Layout;
 use crate::layout::LayoutConfig;
+use crate::overlays::Overlays;
+use crate::speed::Speed;
 use crate::stable_loop::StableLoop;
+use crate::theme::Theme;
 
 const TARGET_FPS: f64 = 60.0;
 
 const MAZE_WIDTH: i64 = 16;
 const MAZE_HEIGHT: i64 = 16;
+const MAZE_MIN_SIZE: i64 = 2;
+const MAZE_MAX_SIZE: i64 = 128;
+
+const MAZE_TOPOLOGY: Topology = Topology::Orthogonal;
+const MAZE_ALGORITHM: Algorithm = Algorithm::RecursiveBacktracker;
+
+/// How many generation steps to take every update while auto-stepping
+const GENERATION_SPEED: Speed = Speed::StepsPerUpdate(1.0);
+/// The generation steps per update that the speed slider goes through, before the max speed
+const SPEED_LEVELS: &[f64] = &[
+    0.1, 0.2, 0.5, 1.0, 2.0, 5.0, 10.0, 20.0, 50.0, 100.0, 200.0, 500.0,
+];
+/// How much of every frame the max speed spends generating, leaving the rest for drawing
+const MAX_SPEED_FRAME_BUDGET: f64 = 0.5;
+/// How many steps the max speed takes between checking the time
+const MAX_SPEED_BATCH_SIZE: u32 = 64;
 
 const LAYOUT_CONFIG: LayoutConfig = LayoutConfig {
     padding: 0.015,
 
     maze_width: 1.0,
     maze_height: 1.0,
+    maze_square_cells: true,
     maze_wall_thickness: 0.004,
+    maze_weave_inset: 0.2,
+
+    ui_height: GFX_UI_HEIGHT as f64 / GFX_MAZE_WIDTH as f64,
+    ui_padding: 0.08,
+    panel_width: GFX_PANEL_WIDTH as f64 / GFX_MAZE_WIDTH as f64,
 };
 
-const GFX_MAZE_X: i64 = 0;
-const GFX_MAZE_Y: i64 = 0;
 const GFX_MAZE_WIDTH: i64 = 768;
 const GFX_MAZE_HEIGHT: i64 = 768;
 
-const GFX_UI_X: i64 = 0;
-const GFX_UI_Y: i64 = GFX_MAZE_HEIGHT;
-const GFX_UI_WIDTH: i64 = GFX_MAZE_WIDTH;
-const GFX_UI_HEIGHT: i64 = 96;
-const GFX_UI_PADDING: i64 = 8;
+const GFX_UI_HEIGHT: i64 = 128;
+/// How many rows of widgets the button bar has
+const GFX_UI_ROWS: usize = 2;
+
+const GFX_PANEL_WIDTH: i64 = 256;
+/// How many widgets fit in the settings panel, one per row
+const GFX_PANEL_ROWS: usize = 12;
 
 const WINDOW_WIDTH: i64 = GFX_MAZE_WIDTH;
 const WINDOW_HEIGHT: i64 = GFX_MAZE_HEIGHT + GFX_UI_HEIGHT;
 
 const FONT: &str = "font/Cabin-Bold.ttf";
 const FONT_SIZE: u16 = 32;
+/// How much of a button its text may take up, at most
+const TEXT_FIT: f64 = 0.8;
+
+/// The height of every line of the HUD, in pixels
+const GFX_HUD_LINE_HEIGHT: u32 = 24;
+/// How far below and to the right of the mouse a tooltip is, in pixels
+const GFX_TOOLTIP_OFFSET: i32 = 16;
+
+const THEME: Theme = Theme::DUSK;
 
-const BACKGROUND_COLOR: Color = hex_rgb(0x28242E);
-const WALL_COLOR: Color = hex_rgb(0xFFFBDE);
-const CURSOR_COLOR: Color = hex_rgb(0xBD516D);
-const VISITED_CELL_COLOR: Color = hex_rgb(0x6F9D81);
-const UI_COLOR: Color = hex_rgb(0x344B68);
-const UI_BUTTON_COLOR: Color = hex_rgb(0x5378A7);
-const UI_BUTTON_HIGHLIGHT_COLOR: Color = hex_rgb(0x81C0C6);
-const UI_BUTTON_CLICKED_COLOR: Color = hex_rgb(0x436187);
-const UI_BUTTON_TEXT_COLOR: Color = BACKGROUND_COLOR;
+/// How close to a cell's edge a click in the editor has to be to toggle its wall,
+/// as a fraction of the cell's size
+const EDIT_EDGE_REACH: f64 = 0.3;
+
+/// Rebinds keys when it exists, unless another file is given on the command line
+const KEY_MAP_FILE: &str = "keys.txt";
+
+/// The longest side of exported images, in pixels
+const EXPORT_SIZE: u32 = 1024;
+
+/// The longest side of recorded animation frames, in pixels
+const RECORDING_SIZE: u32 = 512;
+const RECORDING_FRAME_SKIP: u32 = 1;
+/// In milliseconds
+const RECORDING_FRAME_DELAY: u32 = 50;
+/// How long the finished maze is shown at the end of a recording, in milliseconds
+const RECORDING_FINAL_FRAME_DELAY: u32 = 2000;
+/// Trades off the quality of GIF colors for encoding time, from 1 (best) to 30
+const GIF_SPEED: i32 = 10;
 
 fn main() {
+    let args = match Args::parse(env::args().skip(1)) {
+        Ok(args) => args,
+
+        Err(e) => {
+            eprintln!("{}\n\n{}", e, Args::usage());
+            process::exit(2);
+        }
+    };
+
+    if args.help {
+        print!("{}", Args::usage());
+        return;
+    }
+
     let layout = Layout::new(LAYOUT_CONFIG);
 
-    let (mut ctx, ttf_ctx) =
-        Context::new("Maze Generator", WINDOW_WIDTH as u32, WINDOW_HEIGHT as u32).unwrap();
+    let (maze_settings, maze, mut rng) = match (&args.load, &args.code, &args.import) {
+        (Some(load_path), _, _) => {
+            let saved = load_maze(load_path).unwrap_or_else(|e| {
+                eprintln!("{}: {}", load_path.display(), e);
+                process::exit(1);
+            });
+
+            let maze_settings = MazeSettings::from_maze(&saved.maze, saved.seed);
+
+            (maze_settings, saved.maze, saved.rng)
+        }
+
+        (None, Some(code), _) => {
+            let maze = share::from_code(code).unwrap_or_else(|e| {
+                eprintln!("{}", e);
+                process::exit(1);
+            });
+
+            // The maze is already finished, but the seed is still used if it's resized
+            let maze_settings =
+                MazeSettings::from_maze(&maze, args.seed.unwrap_or_else(rand::random));
+            let rng = maze_settings.build_rng();
+
+            (maze_settings, maze, rng)
+        }
+
+        (None, None, Some(import_path)) => {
+            let maze = import_maze(import_path, &args, &layout).unwrap_or_else(|e| {
+                eprintln!("{}: {}", import_path.display(), e);
+                process::exit(1);
+            });
+
+            let maze_settings =
+                MazeSettings::from_maze(&maze, args.seed.unwrap_or_else(rand::random));
+            let rng = maze_settings.build_rng();
+
+            (maze_settings, maze, rng)
+        }
+
+        (None, None, None) => {
+            let maze_settings = new_maze_settings(&args);
+
+            let maze = maze_settings.build_maze().unwrap_or_else(|e| {
+                eprintln!(
+                    "Failed to create a {}x{} maze: {}",
+                    maze_settings.width, maze_settings.height, e
+                );
+                process::exit(1);
+            });
+            let rng = maze_settings.build_rng();
+
+            (maze_settings, maze, rng)
+        }
+    };
+
+    let view_settings = ViewSettings {
+        speed: args.speed.unwrap_or(GENERATION_SPEED),
+        theme: args.theme.unwrap_or(THEME),
+        overlays: Overlays {
+            solution: args.solution,
+            solver: args.solver.unwrap_or_default(),
+            heatmap: args.heatmap,
+        },
+    };
+
+    // Recording and exporting happen instead of opening a window
+    if args.record.is_some() || args.export.is_some() {
+        let mut maze = maze;
+
+        if let Some(record_path) = &args.record {
+            let recording = Recording {
+                frame_skip: args.frame_skip.unwrap_or(RECORDING_FRAME_SKIP),
+                frame_delay: args.frame_delay.unwrap_or(RECORDING_FRAME_DELAY),
+            };
+
+            if let Err(e) = export::record(
+                record_path,
+                &mut maze,
+                &mut rng,
+                &layout,
+                &view_settings.theme,
+                view_settings.overlays,
+                recording,
+            ) {
+                eprintln!("{}: {}", record_path.display(), e);
+                process::exit(1);
+            }
+        }
+
+        if let Some(export_path) = &args.export {
+            maze.finish(&mut rng);
+
+            if let Err(e) = export::export(
+                export_path,
+                &maze,
+                &layout,
+                &view_settings.theme,
+                view_settings.overlays,
+            ) {
+                eprintln!("{}: {}", export_path.display(), e);
+                process::exit(1);
+            }
+        }
+
+        return;
+    }
+
+    let key_map = load_key_map(&args);
+
+    let (window_width, window_height) = args
+        .window_size
+        .unwrap_or((WINDOW_WIDTH as u32, WINDOW_HEIGHT as u32));
+
+    let (mut ctx, ttf_ctx) = Context::new(
+        "Maze Generator",
+        window_width,
+        window_height,
+        args.fullscreen,
+    )
+    .unwrap();
 
     let ctx_ref;
     let ttf_ctx_ref;
@@ -82,13 +282,86 @@ fn main() {
     }
 
     let mut app = App::new(
-        MAZE_WIDTH,
-        MAZE_HEIGHT,
+        maze_settings,
+        maze,
+        rng,
+        view_settings,
         layout,
         ttf_ctx_ref,
         ctx_ref.canvas(),
     )
-    .unwrap();
+    .unwrap()
+    .with_key_map(key_map);
 
     app.main_loop(ctx_ref);
 }
+
+/// The settings for a new maze, from the command line and the defaults
+fn new_maze_settings(args: &Args) -> MazeSettings {
+    let mask = args.mask.as_ref().map(|mask_path| {
+        Mask::load(mask_path)
+            .and_then(|mask| mask.within_size(MAZE_MAX_SIZE))
+            .unwrap_or_else(|e| {
+                eprintln!("{}: {}", mask_path.display(), e);
+                process::exit(1);
+            })
+    });
+
+    // A mask has its own size, but it's resampled when given an explicit one
+    let (default_width, default_height) = match &mask {
+        Some(mask) => (mask.width(), mask.height()),
+        None => (MAZE_WIDTH, MAZE_HEIGHT),
+    };
+    let width = args.maze_width.unwrap_or(default_width);
+    let height = args.maze_height.unwrap_or(default_height);
+
+    MazeSettings {
+        width,
+        height,
+        keep_square: width == height,
+        mask,
+        topology: args.topology.unwrap_or(MAZE_TOPOLOGY),
+        algorithm: args.algorithm.unwrap_or(MAZE_ALGORITHM),
+        seed: args.seed.unwrap_or_else(rand::random),
+    }
+}
+
+/// The key bindings from the key map file, or the default ones when there isn't one
+fn load_key_map(args: &Args) -> KeyMap {
+    let path = match &args.keys {
+        Some(path) => path.as_path(),
+        None if Path::new(KEY_MAP_FILE).exists() => Path::new(KEY_MAP_FILE),
+        None => return KeyMap::default(),
+    };
+
+    KeyMap::load(path).unwrap_or_else(|e| {
+        eprintln!("{}: {}", path.display(), e);
+        process::exit(1);
+    })
+}
+
+fn load_maze(path: &Path) -> Result<SavedMaze, Box<dyn Error>> {
+    let json = fs::read_to_string(path)?;
+
+    Ok(save::from_json(&json)?)
+}
+
+/// Reads a maze from a PNG exported with the same layout, with the size given on the command line
+fn import_maze(path: &Path, args: &Args, layout: &Layout) -> Result<Maze, ImportError> {
+    let image = Image::load_png(path).map_err(ImportError::Image)?;
+
+    let window_layout = layout.without_ui().apply(
+        image.width() as u32,
+        image.height() as u32,
+        args.maze_width.unwrap_or(MAZE_WIDTH) as usize,
+        args.maze_height.unwrap_or(MAZE_HEIGHT) as usize,
+    );
+    let positions = |positions: &[i32]| positions.iter().map(|&position| position as i64).collect();
+
+    let grid = Grid {
+        x_positions: positions(&window_layout.maze.cell_x_positions),
+        y_positions: positions(&window_layout.maze.cell_y_positions),
+    };
+
+    import::from_image(&image, &grid)
+}
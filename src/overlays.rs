@@ -0,0 +1,12 @@
+use maze_gen::solve::Solver;
+
+/// Extra information drawn on top of a maze
+#[derive(Clone, Copy, Default)]
+pub struct Overlays {
+    /// The path from the start to the goal
+    pub solution: bool,
+    /// How the path to the goal is found
+    pub solver: Solver,
+    /// Every cell colored by its distance from the start
+    pub heatmap: bool,
+}
@@ -5,8 +5,9 @@ use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Sub, SubAssign};
 use sdl2::rect::Point as SdlPoint;
 use sdl2::video::Window;
 
+use maze_gen::maze::Maze;
+
 use crate::layout::Layout;
-use crate::maze::Maze;
 
 pub struct Point<S: Space> {
     pub x: S::Number,
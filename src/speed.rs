@@ -0,0 +1,129 @@
+use crate::SPEED_LEVELS;
+
+/// How fast the maze is generated while auto-stepping
+#[derive(Clone, Copy, PartialEq)]
+pub enum Speed {
+    /// Generation steps every update, where rates below 1 take a step every few updates
+    StepsPerUpdate(f64),
+    /// As many steps as fit in part of every frame
+    Max,
+}
+
+impl Speed {
+    /// The speeds the slider and the keyboard shortcuts go through, from slowest to fastest
+    pub fn levels() -> Vec<Speed> {
+        SPEED_LEVELS
+            .iter()
+            .map(|&steps| Speed::StepsPerUpdate(steps))
+            .chain([Speed::Max])
+            .collect()
+    }
+
+    /// The index of the level closest to this speed
+    pub fn level(&self) -> usize {
+        let levels = Self::levels();
+
+        match self {
+            Speed::Max => levels.len() - 1,
+
+            &Speed::StepsPerUpdate(steps) => SPEED_LEVELS
+                .iter()
+                .enumerate()
+                .min_by(|(_, a), (_, b)| (*a - steps).abs().total_cmp(&(*b - steps).abs()))
+                .map_or(0, |(level, _)| level),
+        }
+    }
+
+    /// The next faster or slower level, staying within the slowest and fastest ones
+    pub fn changed_by(&self, levels: isize) -> Speed {
+        let all_levels = Self::levels();
+        let level = self
+            .level()
+            .saturating_add_signed(levels)
+            .min(all_levels.len() - 1);
+
+        all_levels[level]
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Speed::StepsPerUpdate(steps) => steps.to_string(),
+            Speed::Max => "max".to_string(),
+        }
+    }
+
+    /// Parses either a number of steps per update, which may be fractional, or `max`
+    pub fn from_name(name: &str) -> Option<Self> {
+        if name == "max" {
+            return Some(Speed::Max);
+        }
+
+        name.parse()
+            .ok()
+            .filter(|steps: &f64| steps.is_finite() && *steps >= 0.0)
+            .map(Speed::StepsPerUpdate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_level_is_its_own_level() {
+        for (level, speed) in Speed::levels().into_iter().enumerate() {
+            assert_eq!(speed.level(), level);
+        }
+    }
+
+    #[test]
+    fn other_speeds_are_at_the_closest_level() {
+        let slowest = SPEED_LEVELS[0];
+        let fastest = SPEED_LEVELS[SPEED_LEVELS.len() - 1];
+
+        assert_eq!(Speed::StepsPerUpdate(0.0).level(), 0);
+        assert_eq!(Speed::StepsPerUpdate(slowest * 1.01).level(), 0);
+        assert_eq!(
+            Speed::StepsPerUpdate(fastest * 100.0).level(),
+            SPEED_LEVELS.len() - 1
+        );
+    }
+
+    #[test]
+    fn changing_speed_stops_at_the_slowest_and_fastest_levels() {
+        let levels = Speed::levels();
+        let slowest = levels[0];
+
+        assert!(slowest.changed_by(-1) == slowest);
+        assert!(slowest.changed_by(isize::MIN) == slowest);
+        assert!(slowest.changed_by(1) == levels[1]);
+
+        assert!(Speed::Max.changed_by(1) == Speed::Max);
+        assert!(Speed::Max.changed_by(isize::MAX) == Speed::Max);
+        assert!(Speed::Max.changed_by(-1) == levels[levels.len() - 2]);
+    }
+
+    #[test]
+    fn the_fastest_steps_per_update_go_up_to_max() {
+        let fastest = Speed::StepsPerUpdate(SPEED_LEVELS[SPEED_LEVELS.len() - 1]);
+
+        assert!(fastest.changed_by(1) == Speed::Max);
+        assert!(Speed::StepsPerUpdate(0.1).changed_by(isize::MAX) == Speed::Max);
+    }
+
+    #[test]
+    fn names_are_parsed_back() {
+        for speed in Speed::levels() {
+            assert!(Speed::from_name(&speed.name()) == Some(speed));
+        }
+
+        assert!(Speed::from_name("0.25") == Some(Speed::StepsPerUpdate(0.25)));
+    }
+
+    #[test]
+    fn invalid_names_are_rejected() {
+        for name in ["", "fast", "MAX", "-1", "NaN", "inf"] {
+            assert!(Speed::from_name(name).is_none(), "{}", name);
+        }
+    }
+}
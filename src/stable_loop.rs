@@ -139,7 +139,8 @@ impl StableLoopState {
     }
 }
 
-struct Time {
+/// A clock that also works in the browser, where `Instant` isn't available
+pub(crate) struct Time {
     #[cfg(not(target_os = "emscripten"))]
     start_time: Instant,
 
@@ -148,7 +149,7 @@ struct Time {
 }
 
 impl Time {
-    fn new() -> Self {
+    pub(crate) fn new() -> Self {
         #[cfg(not(target_os = "emscripten"))]
         let start_time = Instant::now();
 
@@ -158,7 +159,7 @@ impl Time {
         Self { start_time }
     }
 
-    fn time_since_start(&self) -> Duration {
+    pub(crate) fn time_since_start(&self) -> Duration {
         #[cfg(not(target_os = "emscripten"))]
         return self.start_time.elapsed();
 
@@ -0,0 +1,131 @@
+use sdl2::pixels::Color;
+use sdl2::rect::Rect;
+
+use maze_gen::maze::Maze;
+use maze_gen::solve;
+
+use crate::layout::Layout;
+use crate::overlays::Overlays;
+use crate::point_new::Point;
+use crate::theme::Theme;
+
+/// Renders the maze as an SVG image of the given size in pixels, laid out the same as on screen
+///
+/// The walls are line segments, so the image can be used for cutting as well as printing.
+/// The start and goal are always marked, and the overlays are drawn on request.
+pub fn render(
+    maze: &Maze,
+    layout: &Layout,
+    theme: &Theme,
+    overlays: Overlays,
+    width: u32,
+    height: u32,
+) -> String {
+    let window_layout = layout.apply(width, height, maze.width() as usize, maze.height() as usize);
+    let layout = &window_layout.maze;
+
+    let mut svg = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}\" height=\"{height}\" \
+         viewBox=\"0 0 {width} {height}\">\n"
+    );
+
+    svg += &format!("<g id=\"cells\" fill=\"{}\">\n", hex(theme.background));
+    for cell in maze.all_cells() {
+        svg += &rect(
+            layout.cell_rect(Point::new(cell.x as usize, cell.y as usize)),
+            None,
+        );
+    }
+    svg += "</g>\n";
+
+    if overlays.heatmap {
+        let distances = solve::distances(maze, maze.start());
+        let max_distance = distances.values().copied().max().unwrap_or(0).max(1);
+
+        svg += "<g id=\"heatmap\">\n";
+        for (&cell, &distance) in &distances {
+            let color = theme.heatmap(distance as f64 / max_distance as f64);
+
+            for floor_rect in layout.cell_floor(maze, cell) {
+                svg += &rect(floor_rect, Some(color));
+            }
+        }
+        svg += "</g>\n";
+    }
+
+    svg += &format!(
+        "<g id=\"walls\" stroke=\"{}\" stroke-width=\"{}\" stroke-linecap=\"square\">\n",
+        hex(theme.wall),
+        layout.wall_thickness,
+    );
+    for (start, end) in layout.wall_lines(maze) {
+        svg += &format!(
+            "<line x1=\"{}\" y1=\"{}\" x2=\"{}\" y2=\"{}\"/>\n",
+            start.x, start.y, end.x, end.y
+        );
+    }
+    svg += "</g>\n";
+
+    let (_, marker_size) = layout.cell_center(maze.start());
+
+    if overlays.solution {
+        if let Some(solution) = solve::solve(maze, overlays.solver) {
+            let points: Vec<String> = solution
+                .into_iter()
+                .map(|cell| {
+                    let (center, _) = layout.cell_center(cell);
+                    format!("{},{}", center.x, center.y)
+                })
+                .collect();
+
+            svg += &format!(
+                "<polyline id=\"solution\" points=\"{}\" fill=\"none\" stroke=\"{}\" \
+                 stroke-width=\"{}\" stroke-linecap=\"round\" stroke-linejoin=\"round\"/>\n",
+                points.join(" "),
+                hex(theme.solution),
+                (marker_size / 2).max(1),
+            );
+        }
+    }
+
+    for (id, cell, color) in [
+        ("start", maze.start(), theme.start),
+        ("goal", maze.goal(), theme.goal),
+    ] {
+        let (center, size) = layout.cell_center(cell);
+
+        svg += &format!(
+            "<circle id=\"{}\" cx=\"{}\" cy=\"{}\" r=\"{}\" fill=\"{}\"/>\n",
+            id,
+            center.x,
+            center.y,
+            size / 2,
+            hex(color),
+        );
+    }
+
+    svg += "</svg>\n";
+
+    svg
+}
+
+fn rect(rect: Rect, fill: Option<Color>) -> String {
+    let fill = match fill {
+        Some(color) => format!(" fill=\"{}\"", hex(color)),
+        None => String::new(),
+    };
+
+    format!(
+        "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\"{}/>\n",
+        rect.x(),
+        rect.y(),
+        rect.width(),
+        rect.height(),
+        fill,
+    )
+}
+
+fn hex(color: Color) -> String {
+    format!("#{:02x}{:02x}{:02x}", color.r, color.g, color.b)
+}
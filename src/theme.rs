@@ -0,0 +1,62 @@
+use sdl2::pixels::Color;
+
+use crate::color_blend::ColorBlend;
+use crate::color_hex::hex_rgb;
+
+/// The colors everything is drawn with
+#[derive(Clone, Copy, PartialEq)]
+pub struct Theme {
+    pub background: Color,
+    pub wall: Color,
+    pub cursor: Color,
+    pub visited_cell: Color,
+    pub start: Color,
+    pub goal: Color,
+    pub solution: Color,
+    /// The heatmap fades from `heatmap_near` at the start to `heatmap_far` at the farthest cell
+    pub heatmap_near: Color,
+    pub heatmap_far: Color,
+    pub ui: Color,
+    pub ui_button: Color,
+    pub ui_button_highlight: Color,
+    pub ui_button_clicked: Color,
+    pub ui_button_text: Color,
+    /// Marks text the app couldn't use
+    pub ui_invalid: Color,
+}
+
+impl Theme {
+    pub const DUSK: Theme = Theme {
+        background: hex_rgb(0x28242E),
+        wall: hex_rgb(0xFFFBDE),
+        cursor: hex_rgb(0xBD516D),
+        visited_cell: hex_rgb(0x6F9D81),
+        start: hex_rgb(0x81C0C6),
+        goal: hex_rgb(0xE8B04B),
+        solution: hex_rgb(0xBD516D),
+        heatmap_near: hex_rgb(0x344B68),
+        heatmap_far: hex_rgb(0xBD516D),
+        ui: hex_rgb(0x344B68),
+        ui_button: hex_rgb(0x5378A7),
+        ui_button_highlight: hex_rgb(0x81C0C6),
+        ui_button_clicked: hex_rgb(0x436187),
+        ui_button_text: hex_rgb(0x28242E),
+        ui_invalid: hex_rgb(0xBD516D),
+    };
+
+    pub fn all() -> &'static [(&'static str, Theme)] {
+        &[("dusk", Theme::DUSK)]
+    }
+
+    /// The heatmap color of a cell, from 0 at the start to 1 at the farthest cell
+    pub fn heatmap(&self, factor: f64) -> Color {
+        self.heatmap_near.blend(self.heatmap_far, factor)
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::all()
+            .iter()
+            .find(|(theme_name, _)| *theme_name == name)
+            .map(|&(_, theme)| theme)
+    }
+}
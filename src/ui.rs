@@ -1,162 +1,1205 @@
 use std::collections::BTreeMap;
+use std::ops::Range;
 
-use crate::point::WindowPoint;
-use crate::{GFX_UI_HEIGHT, GFX_UI_PADDING, GFX_UI_WIDTH, GFX_UI_X, GFX_UI_Y};
+use sdl2::keyboard::{Mod, Scancode};
+
+use crate::layout::WindowUiLayout;
+use crate::window_point::WindowPoint;
+use crate::{GFX_PANEL_ROWS, GFX_UI_ROWS};
 
 pub struct Ui {
     buttons: BTreeMap<ButtonId, Button>,
-    pressed_button_id: Option<ButtonId>,
+    sliders: BTreeMap<SliderId, Slider>,
+    dropdowns: BTreeMap<DropdownId, Dropdown>,
+    spinners: BTreeMap<SpinnerId, Spinner>,
+    checkboxes: BTreeMap<CheckboxId, Checkbox>,
+    text_fields: BTreeMap<TextFieldId, TextField>,
+    pressed_widget_id: Option<WidgetId>,
 }
 
 impl Ui {
+    /// Creates the widgets, which have no size until the layout is applied
     pub fn new() -> Self {
-        let button_count = ButtonId::all().len() as i64;
-        let button_width =
-            (GFX_UI_WIDTH - GFX_UI_PADDING - button_count * GFX_UI_PADDING) / button_count;
-        let button_height = GFX_UI_HEIGHT - GFX_UI_PADDING * 2;
-
-        let mut buttons = BTreeMap::new();
-
-        for (i, button_id) in ButtonId::all().iter().copied().enumerate() {
-            let x = GFX_UI_X + GFX_UI_PADDING + ((button_width + GFX_UI_PADDING) * i as i64);
-            let y = GFX_UI_Y + GFX_UI_PADDING;
-
-            let button = Button::new(
-                button_id,
-                WindowPoint::new(x, y),
-                button_width,
-                button_height,
-            );
+        Self {
+            buttons: ButtonId::all()
+                .iter()
+                .map(|&button_id| (button_id, Button::new(button_id)))
+                .collect(),
+            sliders: SliderId::all()
+                .iter()
+                .map(|&slider_id| (slider_id, Slider::new()))
+                .collect(),
+            dropdowns: DropdownId::all()
+                .iter()
+                .map(|&dropdown_id| (dropdown_id, Dropdown::new(dropdown_id)))
+                .collect(),
+            spinners: SpinnerId::all()
+                .iter()
+                .map(|&spinner_id| (spinner_id, Spinner::new(spinner_id)))
+                .collect(),
+            checkboxes: CheckboxId::all()
+                .iter()
+                .map(|&checkbox_id| (checkbox_id, Checkbox::new(checkbox_id)))
+                .collect(),
+            text_fields: TextFieldId::all()
+                .iter()
+                .map(|&text_field_id| (text_field_id, TextField::new(text_field_id)))
+                .collect(),
+            pressed_widget_id: None,
+        }
+    }
+
+    /// Places the widgets in the button bar and the settings panel, which change with the window
+    ///
+    /// The widgets in a hidden area are hidden too.
+    pub fn apply_layout(&mut self, bar: &WindowUiLayout, panel: &WindowUiLayout) {
+        for (widgets, layout, rows, opens_upwards) in [
+            (Widget::bar(), bar, GFX_UI_ROWS, true),
+            (Widget::panel(), panel, GFX_PANEL_ROWS, false),
+        ] {
+            let rects = match layout.rect() {
+                Some(_) => layout.widget_rects(widgets.len(), rows),
+                None => Vec::new(),
+            };
+
+            for (i, widget) in widgets.into_iter().enumerate() {
+                *self.bounds_mut(widget) = rects.get(i).map_or(Bounds::default(), |rect| Bounds {
+                    position: WindowPoint::new(rect.x() as i64, rect.y() as i64),
+                    width: rect.width() as i64,
+                    height: rect.height() as i64,
+                });
 
-            buttons.insert(button_id, button);
+                // Lists open away from the edge of the window the bar is on
+                if let Widget::Dropdown(dropdown_id) = widget {
+                    let dropdown = self.dropdowns.get_mut(&dropdown_id).unwrap();
+                    dropdown.opens_upwards = opens_upwards;
+
+                    if dropdown.bounds.is_empty() {
+                        dropdown.is_open = false;
+                    }
+                }
+            }
         }
 
-        Self {
-            buttons,
-            pressed_button_id: None,
+        if self
+            .focused_text_field()
+            .is_some_and(|text_field_id| self.text_fields[&text_field_id].bounds.is_empty())
+        {
+            self.stop_typing();
+        }
+    }
+
+    /// Switches a button to its alternate text, for buttons that toggle something on and off
+    pub fn set_toggled(&mut self, button_id: ButtonId, is_toggled: bool) {
+        if let Some(button) = self.buttons.get_mut(&button_id) {
+            button.is_toggled = is_toggled;
+        }
+    }
+
+    /// Greys out a button, so that it can't be clicked until it's enabled again
+    pub fn set_button_enabled(&mut self, button_id: ButtonId, is_enabled: bool) {
+        if let Some(button) = self.buttons.get_mut(&button_id) {
+            button.is_enabled = is_enabled;
+        }
+    }
+
+    /// Greys out a slider, so that it can't be dragged until it's enabled again
+    pub fn set_slider_enabled(&mut self, slider_id: SliderId, is_enabled: bool) {
+        if let Some(slider) = self.sliders.get_mut(&slider_id) {
+            slider.is_enabled = is_enabled;
         }
     }
 
+    /// Moves a slider to one of its values, from 0 to `value_count - 1`, and sets its text
+    pub fn set_slider(
+        &mut self,
+        slider_id: SliderId,
+        value: usize,
+        value_count: usize,
+        text: String,
+    ) {
+        if let Some(slider) = self.sliders.get_mut(&slider_id) {
+            slider.value = value;
+            slider.value_count = value_count;
+            slider.text = text;
+        }
+    }
+
+    /// Sets the options a dropdown picks from, and which one is picked
+    pub fn set_dropdown(&mut self, dropdown_id: DropdownId, options: &[&str], selected: usize) {
+        if let Some(dropdown) = self.dropdowns.get_mut(&dropdown_id) {
+            if dropdown.options.len() != options.len() {
+                dropdown.option_states = options.iter().map(|_| ButtonState::new()).collect();
+            }
+
+            dropdown.options = options.iter().map(|option| option.to_string()).collect();
+            dropdown.selected = selected;
+        }
+    }
+
+    /// Sets a spinner's value, and the range it can be changed within
+    pub fn set_spinner(&mut self, spinner_id: SpinnerId, value: i64, min: i64, max: i64) {
+        if let Some(spinner) = self.spinners.get_mut(&spinner_id) {
+            spinner.value = value;
+            spinner.min = min;
+            spinner.max = max;
+        }
+    }
+
+    pub fn set_checkbox(&mut self, checkbox_id: CheckboxId, is_checked: bool) {
+        if let Some(checkbox) = self.checkboxes.get_mut(&checkbox_id) {
+            checkbox.is_checked = is_checked;
+        }
+    }
+
+    /// Replaces a text field's text, unless it's being typed in
+    pub fn set_text_field(&mut self, text_field_id: TextFieldId, text: &str) {
+        if let Some(text_field) = self.text_fields.get_mut(&text_field_id) {
+            if !text_field.is_focused && text_field.text != text {
+                text_field.text = text.to_string();
+                text_field.cursor = text_field.char_count();
+                text_field.is_valid = true;
+            }
+        }
+    }
+
+    /// Marks a text field's text as something the app can't use, until it's edited
+    pub fn set_text_field_invalid(&mut self, text_field_id: TextFieldId) {
+        if let Some(text_field) = self.text_fields.get_mut(&text_field_id) {
+            text_field.is_valid = false;
+        }
+    }
+
+    pub fn text_field_text(&self, text_field_id: TextFieldId) -> &str {
+        &self.text_fields[&text_field_id].text
+    }
+
+    /// The text field that the keyboard types in, if any
+    pub fn focused_text_field(&self) -> Option<TextFieldId> {
+        self.text_fields
+            .values()
+            .find(|text_field| text_field.is_focused)
+            .map(|text_field| text_field.text_field_id)
+    }
+
+    /// The selected text in the focused text field, for copying
+    pub fn selected_text(&self) -> Option<String> {
+        self.text_fields
+            .get(&self.focused_text_field()?)?
+            .selected_text()
+    }
+
+    /// Unfocuses the focused text field, so the keyboard goes back to the app
+    pub fn stop_typing(&mut self) {
+        self.focus_text_field(None);
+    }
+
     pub fn update(&mut self) {
-        for button in self.buttons.values_mut() {
-            button.update();
+        for widget_id in self.widget_ids() {
+            self.state_mut(widget_id).update();
         }
     }
 
-    pub fn on_mouse_move(&mut self, mouse: WindowPoint) {
-        if self.pressed_button_id.is_none() {
-            for button in self.buttons.values_mut() {
-                button.on_mouse_move(mouse);
+    /// Dragging a slider changes its value
+    pub fn on_mouse_move(&mut self, mouse: WindowPoint) -> Option<UiEvent> {
+        match self.pressed_widget_id {
+            None => {
+                let hovered_widget_id = self.widget_at(mouse);
+
+                for widget_id in self.widget_ids() {
+                    self.state_mut(widget_id)
+                        .on_mouse_move(hovered_widget_id == Some(widget_id));
+                }
+
+                None
             }
+
+            Some(WidgetId::Slider(slider_id)) => self.drag_slider(slider_id, mouse),
+
+            Some(_) => None,
         }
     }
 
-    pub fn on_mouse_press(&mut self, mouse: WindowPoint) {
+    pub fn on_mouse_press(&mut self, mouse: WindowPoint) -> Option<UiEvent> {
         self.on_mouse_move(mouse);
 
-        if let Some(pressed_button) = self
-            .buttons
+        let pressed_widget_id = self.widget_at(mouse);
+
+        // Clicking a text field starts typing in it, and clicking anywhere else stops
+        self.focus_text_field(match pressed_widget_id {
+            Some(WidgetId::TextField(text_field_id)) => Some(text_field_id),
+            _ => None,
+        });
+
+        // Clicking anywhere but on an open dropdown closes it, without doing anything else
+        if let Some(open_dropdown) = self
+            .dropdowns
             .values_mut()
-            .find(|button| button.is_point_inside(mouse))
+            .find(|dropdown| dropdown.is_open)
         {
-            pressed_button.on_mouse_press();
+            let dropdown_id = open_dropdown.dropdown_id;
 
-            self.pressed_button_id = Some(pressed_button.button_id);
+            let is_on_dropdown = matches!(
+                pressed_widget_id,
+                Some(WidgetId::Dropdown(id) | WidgetId::DropdownOption(id, _)) if id == dropdown_id
+            );
+
+            if !is_on_dropdown {
+                open_dropdown.is_open = false;
+                return None;
+            }
         }
 
-        self.on_mouse_move(mouse);
+        let pressed_widget_id = pressed_widget_id?;
+
+        self.state_mut(pressed_widget_id).on_mouse_press();
+        self.pressed_widget_id = Some(pressed_widget_id);
+
+        match pressed_widget_id {
+            WidgetId::Slider(slider_id) => self.drag_slider(slider_id, mouse),
+            _ => None,
+        }
     }
 
-    pub fn on_mouse_release(&mut self, mouse: WindowPoint) -> Option<ButtonId> {
-        let mut clicked_button = None;
+    pub fn on_mouse_release(&mut self, mouse: WindowPoint) -> Option<UiEvent> {
+        let mut event = None;
+
+        if let Some(pressed_widget_id) = self.pressed_widget_id.take() {
+            let is_inside = self.widget_at(mouse) == Some(pressed_widget_id);
+
+            self.state_mut(pressed_widget_id)
+                .on_mouse_release(is_inside);
+
+            if is_inside {
+                event = self.on_click(pressed_widget_id);
+            }
+        }
 
         self.on_mouse_move(mouse);
 
-        if let Some(pressed_button_id) = self.pressed_button_id {
-            let button = self.buttons.get_mut(&pressed_button_id).unwrap();
+        event
+    }
 
-            button.on_mouse_release(mouse);
+    /// Types text from SDL's text input events into the focused text field, or pastes it,
+    /// replacing the selection
+    pub fn on_text_input(&mut self, text: &str) {
+        if let Some(text_field) = self
+            .text_fields
+            .values_mut()
+            .find(|text_field| text_field.is_focused)
+        {
+            text_field.insert(text);
+        }
+    }
+
+    /// Edits the focused text field with the keyboard, where Escape stops typing in it
+    pub fn on_key_down(&mut self, scancode: Scancode, keymod: Mod) -> Option<UiEvent> {
+        if scancode == Scancode::Escape {
+            self.stop_typing();
+            return None;
+        }
 
-            if button.is_point_inside(mouse) {
-                clicked_button = Some(pressed_button_id);
+        let is_selecting = keymod.intersects(Mod::LSHIFTMOD | Mod::RSHIFTMOD);
+        let is_ctrl =
+            keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD | Mod::LGUIMOD | Mod::RGUIMOD);
+
+        self.text_fields
+            .values_mut()
+            .find(|text_field| text_field.is_focused)?
+            .on_key_down(scancode, is_selecting, is_ctrl)
+    }
+
+    /// Whether the mouse is over a widget, including the lists of open dropdowns
+    pub fn is_over_widget(&self, mouse: WindowPoint) -> bool {
+        self.widget_at(mouse).is_some()
+    }
+
+    /// Whether a click anywhere would go to a widget, since it would close an open dropdown
+    pub fn is_dropdown_open(&self) -> bool {
+        self.dropdowns.values().any(|dropdown| dropdown.is_open)
+    }
+
+    /// The visible buttons, and likewise for the other kinds of widgets below
+    pub fn buttons(&self) -> impl Iterator<Item = &Button> {
+        self.buttons
+            .values()
+            .filter(|button| !button.bounds.is_empty())
+    }
+
+    pub fn sliders(&self) -> impl Iterator<Item = &Slider> {
+        self.sliders
+            .values()
+            .filter(|slider| !slider.bounds.is_empty())
+    }
+
+    pub fn dropdowns(&self) -> impl Iterator<Item = &Dropdown> {
+        self.dropdowns
+            .values()
+            .filter(|dropdown| !dropdown.bounds.is_empty())
+    }
+
+    pub fn spinners(&self) -> impl Iterator<Item = &Spinner> {
+        self.spinners
+            .values()
+            .filter(|spinner| !spinner.bounds.is_empty())
+    }
+
+    pub fn checkboxes(&self) -> impl Iterator<Item = &Checkbox> {
+        self.checkboxes
+            .values()
+            .filter(|checkbox| !checkbox.bounds.is_empty())
+    }
+
+    pub fn text_fields(&self) -> impl Iterator<Item = &TextField> {
+        self.text_fields
+            .values()
+            .filter(|text_field| !text_field.bounds.is_empty())
+    }
+
+    /// Focusing a text field selects all of its text, so typing replaces it
+    fn focus_text_field(&mut self, focused: Option<TextFieldId>) {
+        for text_field in self.text_fields.values_mut() {
+            let is_focused = focused == Some(text_field.text_field_id);
+
+            if is_focused && !text_field.is_focused {
+                text_field.select_all();
+            } else if !is_focused {
+                text_field.selection_anchor = None;
             }
 
-            self.pressed_button_id = None;
+            text_field.is_focused = is_focused;
         }
+    }
 
-        self.on_mouse_move(mouse);
+    fn on_click(&mut self, widget_id: WidgetId) -> Option<UiEvent> {
+        match widget_id {
+            WidgetId::Button(button_id) => Some(UiEvent::ButtonClicked(button_id)),
+
+            WidgetId::Slider(_) => None,
+
+            WidgetId::Dropdown(dropdown_id) => {
+                let dropdown = self.dropdowns.get_mut(&dropdown_id).unwrap();
+                dropdown.is_open = !dropdown.is_open;
+
+                None
+            }
 
-        clicked_button
+            WidgetId::DropdownOption(dropdown_id, option) => {
+                let dropdown = self.dropdowns.get_mut(&dropdown_id).unwrap();
+                dropdown.is_open = false;
+                dropdown.selected = option;
+
+                Some(UiEvent::DropdownChanged(dropdown_id, option))
+            }
+
+            WidgetId::SpinnerDecrement(spinner_id) => self.spin(spinner_id, -1),
+            WidgetId::SpinnerIncrement(spinner_id) => self.spin(spinner_id, 1),
+
+            WidgetId::Checkbox(checkbox_id) => {
+                let checkbox = self.checkboxes.get_mut(&checkbox_id).unwrap();
+                checkbox.is_checked = !checkbox.is_checked;
+
+                Some(UiEvent::CheckboxChanged(checkbox_id, checkbox.is_checked))
+            }
+
+            WidgetId::TextField(_) => None,
+        }
     }
 
-    pub fn buttons(&self) -> impl Iterator<Item = &Button> {
-        self.buttons.values()
+    fn drag_slider(&mut self, slider_id: SliderId, mouse: WindowPoint) -> Option<UiEvent> {
+        let slider = self.sliders.get_mut(&slider_id).unwrap();
+        let value = slider.value_at(mouse);
+
+        (value != slider.value).then(|| {
+            slider.value = value;
+
+            UiEvent::SliderChanged(slider_id, value)
+        })
+    }
+
+    fn spin(&mut self, spinner_id: SpinnerId, change: i64) -> Option<UiEvent> {
+        let spinner = self.spinners.get_mut(&spinner_id).unwrap();
+        let value = (spinner.value + change).clamp(spinner.min, spinner.max);
+
+        (value != spinner.value).then(|| {
+            spinner.value = value;
+
+            UiEvent::SpinnerChanged(spinner_id, value)
+        })
     }
+
+    /// Every widget, and every part of a widget that can be pressed on its own
+    fn widget_ids(&self) -> Vec<WidgetId> {
+        let buttons = self.buttons.keys().map(|&id| WidgetId::Button(id));
+        let sliders = self.sliders.keys().map(|&id| WidgetId::Slider(id));
+        let dropdowns = self.dropdowns.values().flat_map(|dropdown| {
+            let id = dropdown.dropdown_id;
+
+            (0..dropdown.options.len())
+                .map(move |option| WidgetId::DropdownOption(id, option))
+                .chain([WidgetId::Dropdown(id)])
+        });
+        let spinners = self.spinners.keys().flat_map(|&id| {
+            [
+                WidgetId::SpinnerDecrement(id),
+                WidgetId::SpinnerIncrement(id),
+            ]
+        });
+        let checkboxes = self.checkboxes.keys().map(|&id| WidgetId::Checkbox(id));
+        let text_fields = self.text_fields.keys().map(|&id| WidgetId::TextField(id));
+
+        buttons
+            .chain(sliders)
+            .chain(dropdowns)
+            .chain(spinners)
+            .chain(checkboxes)
+            .chain(text_fields)
+            .collect()
+    }
+
+    /// The widget under the mouse, where open dropdowns are over everything else,
+    /// leaving out disabled widgets
+    fn widget_at(&self, mouse: WindowPoint) -> Option<WidgetId> {
+        let open_option = self
+            .dropdowns
+            .values()
+            .filter(|dropdown| dropdown.is_open)
+            .find_map(|dropdown| {
+                (0..dropdown.options.len())
+                    .find(|&option| dropdown.option_bounds(option).is_point_inside(mouse))
+                    .map(|option| WidgetId::DropdownOption(dropdown.dropdown_id, option))
+            });
+
+        open_option.or_else(|| {
+            self.widget_ids()
+                .into_iter()
+                .filter(|widget_id| !matches!(widget_id, WidgetId::DropdownOption(..)))
+                .filter(|&widget_id| self.is_enabled(widget_id))
+                .find(|&widget_id| self.bounds(widget_id).is_point_inside(mouse))
+        })
+    }
+
+    fn is_enabled(&self, widget_id: WidgetId) -> bool {
+        match widget_id {
+            WidgetId::Button(id) => self.buttons[&id].is_enabled,
+            WidgetId::Slider(id) => self.sliders[&id].is_enabled,
+            _ => true,
+        }
+    }
+
+    fn bounds(&self, widget_id: WidgetId) -> Bounds {
+        match widget_id {
+            WidgetId::Button(id) => self.buttons[&id].bounds,
+            WidgetId::Slider(id) => self.sliders[&id].bounds,
+            WidgetId::Dropdown(id) => self.dropdowns[&id].bounds,
+            WidgetId::DropdownOption(id, option) => self.dropdowns[&id].option_bounds(option),
+            WidgetId::SpinnerDecrement(id) => self.spinners[&id].decrement_bounds(),
+            WidgetId::SpinnerIncrement(id) => self.spinners[&id].increment_bounds(),
+            WidgetId::Checkbox(id) => self.checkboxes[&id].bounds,
+            WidgetId::TextField(id) => self.text_fields[&id].bounds,
+        }
+    }
+
+    fn bounds_mut(&mut self, widget: Widget) -> &mut Bounds {
+        match widget {
+            Widget::Button(id) => &mut self.buttons.get_mut(&id).unwrap().bounds,
+            Widget::Slider(id) => &mut self.sliders.get_mut(&id).unwrap().bounds,
+            Widget::Dropdown(id) => &mut self.dropdowns.get_mut(&id).unwrap().bounds,
+            Widget::Spinner(id) => &mut self.spinners.get_mut(&id).unwrap().bounds,
+            Widget::Checkbox(id) => &mut self.checkboxes.get_mut(&id).unwrap().bounds,
+            Widget::TextField(id) => &mut self.text_fields.get_mut(&id).unwrap().bounds,
+        }
+    }
+
+    fn state_mut(&mut self, widget_id: WidgetId) -> &mut ButtonState {
+        match widget_id {
+            WidgetId::Button(id) => &mut self.buttons.get_mut(&id).unwrap().state,
+            WidgetId::Slider(id) => &mut self.sliders.get_mut(&id).unwrap().state,
+            WidgetId::Dropdown(id) => &mut self.dropdowns.get_mut(&id).unwrap().state,
+            WidgetId::DropdownOption(id, option) => {
+                &mut self.dropdowns.get_mut(&id).unwrap().option_states[option]
+            }
+            WidgetId::SpinnerDecrement(id) => {
+                &mut self.spinners.get_mut(&id).unwrap().decrement_state
+            }
+            WidgetId::SpinnerIncrement(id) => {
+                &mut self.spinners.get_mut(&id).unwrap().increment_state
+            }
+            WidgetId::Checkbox(id) => &mut self.checkboxes.get_mut(&id).unwrap().state,
+            WidgetId::TextField(id) => &mut self.text_fields.get_mut(&id).unwrap().state,
+        }
+    }
+}
+
+/// Something the user did with a widget, for the app to act on
+#[derive(Clone, Copy)]
+pub enum UiEvent {
+    ButtonClicked(ButtonId),
+    SliderChanged(SliderId, usize),
+    DropdownChanged(DropdownId, usize),
+    SpinnerChanged(SpinnerId, i64),
+    CheckboxChanged(CheckboxId, bool),
+    /// Return was pressed in a text field, whose text the app reads and either uses or rejects
+    TextSubmitted(TextFieldId),
+}
+
+/// A whole widget, to lay out
+#[derive(Clone, Copy)]
+enum Widget {
+    Button(ButtonId),
+    Slider(SliderId),
+    Dropdown(DropdownId),
+    Spinner(SpinnerId),
+    Checkbox(CheckboxId),
+    TextField(TextFieldId),
+}
+
+impl Widget {
+    /// The widgets in the button bar, from left to right and then top to bottom
+    fn bar() -> Vec<Widget> {
+        vec![
+            Widget::Button(ButtonId::StepBack),
+            Widget::Button(ButtonId::Step),
+            Widget::Button(ButtonId::AutoStepOrPause),
+            Widget::Button(ButtonId::Finish),
+            Widget::Button(ButtonId::Reset),
+            Widget::Slider(SliderId::Timeline),
+            Widget::Slider(SliderId::Speed),
+            Widget::Button(ButtonId::Edit),
+            Widget::Button(ButtonId::Settings),
+        ]
+    }
+
+    /// The widgets in the settings panel, from top to bottom
+    fn panel() -> Vec<Widget> {
+        vec![
+            Widget::Dropdown(DropdownId::Algorithm),
+            Widget::Dropdown(DropdownId::Topology),
+            Widget::Spinner(SpinnerId::Width),
+            Widget::Spinner(SpinnerId::Height),
+            Widget::Checkbox(CheckboxId::KeepSquare),
+            Widget::TextField(TextFieldId::Seed),
+            Widget::Button(ButtonId::NewSeed),
+            Widget::Dropdown(DropdownId::Solver),
+            Widget::Dropdown(DropdownId::Theme),
+            Widget::Checkbox(CheckboxId::Solution),
+            Widget::Checkbox(CheckboxId::Heatmap),
+            Widget::TextField(TextFieldId::ShareCode),
+        ]
+    }
+}
+
+/// A widget, or a part of one, that can be pressed
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum WidgetId {
+    Button(ButtonId),
+    Slider(SliderId),
+    Dropdown(DropdownId),
+    DropdownOption(DropdownId, usize),
+    SpinnerDecrement(SpinnerId),
+    SpinnerIncrement(SpinnerId),
+    Checkbox(CheckboxId),
+    TextField(TextFieldId),
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub enum ButtonId {
+    StepBack,
     Step,
     AutoStepOrPause,
     Finish,
     Reset,
+    Edit,
+    Settings,
+    NewSeed,
 }
 
 impl ButtonId {
     fn all() -> &'static [ButtonId] {
         &[
+            ButtonId::StepBack,
             ButtonId::Step,
             ButtonId::AutoStepOrPause,
             ButtonId::Finish,
             ButtonId::Reset,
+            ButtonId::Edit,
+            ButtonId::Settings,
+            ButtonId::NewSeed,
         ]
     }
 
     fn text(&self) -> (&str, Option<&str>) {
         match self {
+            ButtonId::StepBack => ("Step Back", None),
             ButtonId::Step => ("Step", None),
             ButtonId::AutoStepOrPause => ("Auto-Step", Some("Pause")),
             ButtonId::Finish => ("Finish", None),
-            ButtonId::Reset => ("Reset", None),
+            ButtonId::Reset => ("Reset", Some("Apply & Reset")),
+            ButtonId::Edit => ("Edit", Some("Stop Editing")),
+            ButtonId::Settings => ("Settings", Some("Hide Settings")),
+            ButtonId::NewSeed => ("Random Seed", None),
         }
     }
 }
 
-pub struct Button {
-    pub button_id: ButtonId,
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SliderId {
+    Speed,
+    /// Scrubs through the generation steps taken so far
+    Timeline,
+}
+
+impl SliderId {
+    fn all() -> &'static [SliderId] {
+        &[SliderId::Speed, SliderId::Timeline]
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DropdownId {
+    Algorithm,
+    Topology,
+    Solver,
+    Theme,
+}
+
+impl DropdownId {
+    fn all() -> &'static [DropdownId] {
+        &[
+            DropdownId::Algorithm,
+            DropdownId::Topology,
+            DropdownId::Solver,
+            DropdownId::Theme,
+        ]
+    }
 
+    fn label(&self) -> &str {
+        match self {
+            DropdownId::Algorithm => "Algorithm",
+            DropdownId::Topology => "Topology",
+            DropdownId::Solver => "Solver",
+            DropdownId::Theme => "Theme",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SpinnerId {
+    Width,
+    Height,
+}
+
+impl SpinnerId {
+    fn all() -> &'static [SpinnerId] {
+        &[SpinnerId::Width, SpinnerId::Height]
+    }
+
+    fn label(&self) -> &str {
+        match self {
+            SpinnerId::Width => "Width",
+            SpinnerId::Height => "Height",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum CheckboxId {
+    KeepSquare,
+    Solution,
+    Heatmap,
+}
+
+impl CheckboxId {
+    fn all() -> &'static [CheckboxId] {
+        &[
+            CheckboxId::KeepSquare,
+            CheckboxId::Solution,
+            CheckboxId::Heatmap,
+        ]
+    }
+
+    fn text(&self) -> &str {
+        match self {
+            CheckboxId::KeepSquare => "Keep Square",
+            CheckboxId::Solution => "Solution",
+            CheckboxId::Heatmap => "Heatmap",
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum TextFieldId {
+    Seed,
+    ShareCode,
+}
+
+impl TextFieldId {
+    fn all() -> &'static [TextFieldId] {
+        &[TextFieldId::Seed, TextFieldId::ShareCode]
+    }
+
+    fn placeholder(&self) -> &str {
+        match self {
+            TextFieldId::Seed => "Seed",
+            TextFieldId::ShareCode => "Paste a share code",
+        }
+    }
+
+    /// Whether a character can be typed in, where anything else is left out
+    fn accepts(&self, c: char) -> bool {
+        match self {
+            TextFieldId::Seed => c.is_ascii_digit(),
+            // Share codes are in URL-safe base64
+            TextFieldId::ShareCode => c.is_ascii_alphanumeric() || c == '-' || c == '_',
+        }
+    }
+
+    fn max_length(&self) -> usize {
+        match self {
+            // The digits of the largest seed
+            TextFieldId::Seed => u64::MAX.to_string().len(),
+            TextFieldId::ShareCode => usize::MAX,
+        }
+    }
+}
+
+/// Where a widget is in the window
+#[derive(Clone, Copy)]
+pub struct Bounds {
     pub position: WindowPoint,
     pub width: i64,
     pub height: i64,
+}
+
+impl Bounds {
+    /// Hidden widgets have no size
+    fn is_empty(&self) -> bool {
+        self.width <= 0 || self.height <= 0
+    }
+
+    fn is_point_inside(&self, point: WindowPoint) -> bool {
+        point.x >= self.position.x
+            && point.x < (self.position.x + self.width)
+            && point.y >= self.position.y
+            && point.y < (self.position.y + self.height)
+    }
+}
 
+impl Default for Bounds {
+    fn default() -> Self {
+        Self {
+            position: WindowPoint::new(0, 0),
+            width: 0,
+            height: 0,
+        }
+    }
+}
+
+pub struct Button {
+    pub button_id: ButtonId,
+    pub bounds: Bounds,
     pub state: ButtonState,
+    pub is_toggled: bool,
+    pub is_enabled: bool,
 }
 
 impl Button {
-    fn new(button_id: ButtonId, position: WindowPoint, width: i64, height: i64) -> Self {
-        let state = ButtonState::Normal {
-            is_mouse_over: false,
-            highlight: 0.0,
-        };
-
+    fn new(button_id: ButtonId) -> Self {
         Self {
             button_id,
-            position,
+            bounds: Bounds::default(),
+            state: ButtonState::new(),
+            is_toggled: false,
+            is_enabled: true,
+        }
+    }
+
+    /// The button's text, or its alternate text while it's toggled
+    pub fn text(&self) -> &str {
+        match self.button_id.text() {
+            (_, Some(alternate_text)) if self.is_toggled => alternate_text,
+            (text, _) => text,
+        }
+    }
+}
+
+/// Picks one of a range of values by dragging along it
+pub struct Slider {
+    pub bounds: Bounds,
+    pub state: ButtonState,
+    pub value: usize,
+    pub value_count: usize,
+    pub text: String,
+    pub is_enabled: bool,
+}
+
+impl Slider {
+    fn new() -> Self {
+        Self {
+            bounds: Bounds::default(),
+            state: ButtonState::new(),
+            value: 0,
+            value_count: 1,
+            text: String::new(),
+            is_enabled: true,
+        }
+    }
+
+    /// How far along the slider its value is, from 0 to 1
+    pub fn fraction(&self) -> f64 {
+        if self.value_count > 1 {
+            self.value as f64 / (self.value_count - 1) as f64
+        } else {
+            0.0
+        }
+    }
+
+    /// The value closest to where the mouse is along the slider
+    fn value_at(&self, mouse: WindowPoint) -> usize {
+        let fraction = ((mouse.x - self.bounds.position.x) as f64
+            / self.bounds.width.max(1) as f64)
+            .clamp(0.0, 1.0);
+
+        (fraction * self.value_count.saturating_sub(1) as f64).round() as usize
+    }
+}
+
+/// Picks one of a few options from a list that opens when it's clicked
+pub struct Dropdown {
+    pub dropdown_id: DropdownId,
+    pub bounds: Bounds,
+    pub state: ButtonState,
+    pub options: Vec<String>,
+    pub option_states: Vec<ButtonState>,
+    pub selected: usize,
+    pub is_open: bool,
+    /// Dropdowns in the button bar at the bottom of the window open upwards
+    pub opens_upwards: bool,
+}
+
+impl Dropdown {
+    fn new(dropdown_id: DropdownId) -> Self {
+        Self {
+            dropdown_id,
+            bounds: Bounds::default(),
+            state: ButtonState::new(),
+            options: Vec::new(),
+            option_states: Vec::new(),
+            selected: 0,
+            is_open: false,
+            opens_upwards: false,
+        }
+    }
+
+    /// The dropdown's label and the picked option
+    pub fn text(&self) -> String {
+        let option = self
+            .options
+            .get(self.selected)
+            .map_or("", |option| option.as_str());
+
+        format!("{}: {}", self.dropdown_id.label(), option)
+    }
+
+    /// Where an option is in the open list, with the first one right next to the dropdown
+    pub fn option_bounds(&self, option: usize) -> Bounds {
+        let offset = self.bounds.height * (option as i64 + 1);
+        let offset = if self.opens_upwards { -offset } else { offset };
+
+        Bounds {
+            position: WindowPoint::new(self.bounds.position.x, self.bounds.position.y + offset),
+            ..self.bounds
+        }
+    }
+}
+
+/// Changes a number one at a time, with a button on either side of it
+pub struct Spinner {
+    pub spinner_id: SpinnerId,
+    pub bounds: Bounds,
+    pub decrement_state: ButtonState,
+    pub increment_state: ButtonState,
+    pub value: i64,
+    pub min: i64,
+    pub max: i64,
+}
+
+impl Spinner {
+    fn new(spinner_id: SpinnerId) -> Self {
+        Self {
+            spinner_id,
+            bounds: Bounds::default(),
+            decrement_state: ButtonState::new(),
+            increment_state: ButtonState::new(),
+            value: 0,
+            min: 0,
+            max: 0,
+        }
+    }
+
+    /// The spinner's label and value
+    pub fn text(&self) -> String {
+        format!("{}: {}", self.spinner_id.label(), self.value)
+    }
+
+    pub fn decrement_bounds(&self) -> Bounds {
+        Bounds {
+            width: self.button_width(),
+            ..self.bounds
+        }
+    }
+
+    pub fn increment_bounds(&self) -> Bounds {
+        let width = self.button_width();
+
+        Bounds {
+            position: WindowPoint::new(
+                self.bounds.position.x + self.bounds.width - width,
+                self.bounds.position.y,
+            ),
             width,
-            height,
-            state,
+            ..self.bounds
         }
     }
 
-    pub fn text(&self) -> (&str, Option<&str>) {
-        self.button_id.text()
+    /// The space between the buttons, for the text
+    pub fn value_bounds(&self) -> Bounds {
+        let width = self.button_width();
+
+        Bounds {
+            position: WindowPoint::new(self.bounds.position.x + width, self.bounds.position.y),
+            width: self.bounds.width - width * 2,
+            ..self.bounds
+        }
+    }
+
+    /// The buttons are square, unless the spinner is too narrow for that
+    fn button_width(&self) -> i64 {
+        self.bounds.height.min(self.bounds.width / 3)
+    }
+}
+
+pub struct Checkbox {
+    pub checkbox_id: CheckboxId,
+    pub bounds: Bounds,
+    pub state: ButtonState,
+    pub is_checked: bool,
+}
+
+impl Checkbox {
+    fn new(checkbox_id: CheckboxId) -> Self {
+        Self {
+            checkbox_id,
+            bounds: Bounds::default(),
+            state: ButtonState::new(),
+            is_checked: false,
+        }
+    }
+
+    pub fn text(&self) -> &str {
+        self.checkbox_id.text()
+    }
+}
+
+/// A line of text typed in with the keyboard, which only takes the characters its id allows
+pub struct TextField {
+    pub text_field_id: TextFieldId,
+    pub bounds: Bounds,
+    pub state: ButtonState,
+    pub text: String,
+    /// Where typing goes, counted in characters rather than bytes
+    pub cursor: usize,
+    /// The end of the selection that the cursor isn't at, while text is selected
+    pub selection_anchor: Option<usize>,
+    pub is_focused: bool,
+    /// Cleared when the app rejects the text, until it's edited
+    pub is_valid: bool,
+}
+
+impl TextField {
+    fn new(text_field_id: TextFieldId) -> Self {
+        Self {
+            text_field_id,
+            bounds: Bounds::default(),
+            state: ButtonState::new(),
+            text: String::new(),
+            cursor: 0,
+            selection_anchor: None,
+            is_focused: false,
+            is_valid: true,
+        }
+    }
+
+    /// What's shown while the text field is empty
+    pub fn placeholder(&self) -> &str {
+        self.text_field_id.placeholder()
+    }
+
+    /// The selected characters, if any
+    pub fn selection(&self) -> Option<Range<usize>> {
+        let anchor = self.selection_anchor?;
+
+        (anchor != self.cursor).then(|| anchor.min(self.cursor)..anchor.max(self.cursor))
+    }
+
+    fn selected_text(&self) -> Option<String> {
+        let selection = self.selection()?;
+
+        Some(
+            self.text
+                .chars()
+                .skip(selection.start)
+                .take(selection.len())
+                .collect(),
+        )
+    }
+
+    fn char_count(&self) -> usize {
+        self.text.chars().count()
+    }
+
+    /// Where a character starts in the text, or its end for the index past the last one
+    fn byte_index(&self, index: usize) -> usize {
+        self.text
+            .char_indices()
+            .nth(index)
+            .map_or(self.text.len(), |(byte_index, _)| byte_index)
+    }
+
+    fn select_all(&mut self) {
+        self.selection_anchor = Some(0);
+        self.cursor = self.char_count();
+    }
+
+    /// Moves the cursor, and the selection along with it if `is_selecting` is set
+    fn move_cursor(&mut self, cursor: usize, is_selecting: bool) {
+        if is_selecting {
+            self.selection_anchor.get_or_insert(self.cursor);
+        } else {
+            self.selection_anchor = None;
+        }
+
+        self.cursor = cursor.min(self.char_count());
+    }
+
+    /// Returns whether there was anything selected to delete
+    fn delete_selection(&mut self) -> bool {
+        let Some(selection) = self.selection() else {
+            self.selection_anchor = None;
+            return false;
+        };
+
+        let bytes = self.byte_index(selection.start)..self.byte_index(selection.end);
+        self.text.replace_range(bytes, "");
+
+        self.cursor = selection.start;
+        self.selection_anchor = None;
+        self.is_valid = true;
+
+        true
+    }
+
+    /// Replaces the selection with the characters of `text` that the text field takes,
+    /// as many as fit
+    fn insert(&mut self, text: &str) {
+        self.delete_selection();
+
+        let room = self
+            .text_field_id
+            .max_length()
+            .saturating_sub(self.char_count());
+        let inserted: String = text
+            .chars()
+            .filter(|&c| self.text_field_id.accepts(c))
+            .take(room)
+            .collect();
+
+        let byte_index = self.byte_index(self.cursor);
+        self.text.insert_str(byte_index, &inserted);
+
+        self.cursor += inserted.chars().count();
+        self.is_valid = true;
+    }
+
+    fn on_key_down(
+        &mut self,
+        scancode: Scancode,
+        is_selecting: bool,
+        is_ctrl: bool,
+    ) -> Option<UiEvent> {
+        let cursor = self.cursor;
+
+        match scancode {
+            // Moving without extending the selection goes to the selection's edge
+            Scancode::Left => {
+                let cursor = match self.selection() {
+                    Some(selection) if !is_selecting => selection.start,
+                    _ => cursor.saturating_sub(1),
+                };
+
+                self.move_cursor(cursor, is_selecting);
+            }
+            Scancode::Right => {
+                let cursor = match self.selection() {
+                    Some(selection) if !is_selecting => selection.end,
+                    _ => cursor + 1,
+                };
+
+                self.move_cursor(cursor, is_selecting);
+            }
+            Scancode::Home => self.move_cursor(0, is_selecting),
+            Scancode::End => self.move_cursor(self.char_count(), is_selecting),
+
+            // Deleting a single character selects it first
+            Scancode::Backspace => {
+                let was_selected = self.delete_selection();
+
+                if !was_selected && cursor > 0 {
+                    self.selection_anchor = Some(cursor - 1);
+                    self.delete_selection();
+                }
+            }
+            Scancode::Delete => {
+                let was_selected = self.delete_selection();
+
+                if !was_selected && cursor < self.char_count() {
+                    self.selection_anchor = Some(cursor + 1);
+                    self.delete_selection();
+                }
+            }
+
+            Scancode::A if is_ctrl => self.select_all(),
+
+            Scancode::Return | Scancode::KpEnter => {
+                return Some(UiEvent::TextSubmitted(self.text_field_id));
+            }
+
+            _ => {}
+        }
+
+        None
+    }
+}
+
+/// Whether a widget is being pressed, or else how highlighted it is by the mouse hovering over it
+pub enum ButtonState {
+    Normal { is_mouse_over: bool, highlight: f64 },
+    Pressed,
+}
+
+impl ButtonState {
+    fn new() -> Self {
+        ButtonState::Normal {
+            is_mouse_over: false,
+            highlight: 0.0,
+        }
     }
 
     fn update(&mut self) {
         if let ButtonState::Normal {
             is_mouse_over,
             highlight,
-        } = &mut self.state
+        } = self
         {
             if *is_mouse_over {
                 *highlight += 0.1;
@@ -168,34 +1211,144 @@ impl Button {
         }
     }
 
-    fn on_mouse_move(&mut self, mouse: WindowPoint) {
-        let mouse_over = self.is_point_inside(mouse);
-
-        if let ButtonState::Normal { is_mouse_over, .. } = &mut self.state {
+    fn on_mouse_move(&mut self, mouse_over: bool) {
+        if let ButtonState::Normal { is_mouse_over, .. } = self {
             *is_mouse_over = mouse_over;
         }
     }
 
     fn on_mouse_press(&mut self) {
-        self.state = ButtonState::Pressed;
+        *self = ButtonState::Pressed;
     }
 
-    fn on_mouse_release(&mut self, mouse: WindowPoint) {
-        self.state = ButtonState::Normal {
-            is_mouse_over: self.is_point_inside(mouse),
+    fn on_mouse_release(&mut self, is_mouse_over: bool) {
+        *self = ButtonState::Normal {
+            is_mouse_over,
             highlight: 0.0,
         };
     }
+}
 
-    fn is_point_inside(&self, point: WindowPoint) -> bool {
-        point.x >= self.position.x
-            && point.x < (self.position.x + self.width)
-            && point.y >= self.position.y
-            && point.y < (self.position.y + self.height)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text_field(text_field_id: TextFieldId, text: &str) -> TextField {
+        let mut text_field = TextField::new(text_field_id);
+        text_field.insert(text);
+
+        text_field
     }
-}
 
-pub enum ButtonState {
-    Normal { is_mouse_over: bool, highlight: f64 },
-    Pressed,
+    fn press(text_field: &mut TextField, scancode: Scancode) -> Option<UiEvent> {
+        text_field.on_key_down(scancode, false, false)
+    }
+
+    #[test]
+    fn the_cursor_stays_inside_the_text() {
+        let mut text_field = text_field(TextFieldId::Seed, "123");
+        assert_eq!(text_field.cursor, 3);
+
+        press(&mut text_field, Scancode::Right);
+        assert_eq!(text_field.cursor, 3);
+
+        press(&mut text_field, Scancode::Home);
+        press(&mut text_field, Scancode::Left);
+        assert_eq!(text_field.cursor, 0);
+
+        press(&mut text_field, Scancode::Right);
+        text_field.insert("9");
+        assert_eq!(text_field.text, "1923");
+        assert_eq!(text_field.cursor, 2);
+    }
+
+    #[test]
+    fn moving_without_shift_goes_to_the_edge_of_the_selection() {
+        let mut text_field = text_field(TextFieldId::Seed, "12345");
+        press(&mut text_field, Scancode::Home);
+        press(&mut text_field, Scancode::Right);
+
+        text_field.on_key_down(Scancode::Right, true, false);
+        text_field.on_key_down(Scancode::Right, true, false);
+        assert_eq!(text_field.selection(), Some(1..3));
+        assert_eq!(text_field.selected_text().as_deref(), Some("23"));
+
+        press(&mut text_field, Scancode::Left);
+        assert_eq!(text_field.cursor, 1);
+        assert_eq!(text_field.selection(), None);
+    }
+
+    #[test]
+    fn backspace_and_delete_do_nothing_at_the_ends() {
+        let mut text_field = text_field(TextFieldId::Seed, "12");
+
+        press(&mut text_field, Scancode::Delete);
+        assert_eq!(text_field.text, "12");
+
+        press(&mut text_field, Scancode::Backspace);
+        assert_eq!(text_field.text, "1");
+
+        press(&mut text_field, Scancode::Home);
+        press(&mut text_field, Scancode::Backspace);
+        assert_eq!(text_field.text, "1");
+
+        press(&mut text_field, Scancode::Delete);
+        assert_eq!(text_field.text, "");
+        assert_eq!(text_field.cursor, 0);
+
+        press(&mut text_field, Scancode::Backspace);
+        press(&mut text_field, Scancode::Delete);
+        assert_eq!(text_field.text, "");
+    }
+
+    #[test]
+    fn deleting_a_selection_deletes_all_of_it() {
+        let mut text_field = text_field(TextFieldId::Seed, "12345");
+        text_field.on_key_down(Scancode::A, false, true);
+
+        press(&mut text_field, Scancode::Backspace);
+        assert_eq!(text_field.text, "");
+
+        text_field.insert("12345");
+        text_field.on_key_down(Scancode::Left, true, false);
+        text_field.on_key_down(Scancode::Left, true, false);
+
+        press(&mut text_field, Scancode::Delete);
+        assert_eq!(text_field.text, "123");
+    }
+
+    #[test]
+    fn only_accepted_characters_are_typed() {
+        let seed = text_field(TextFieldId::Seed, "1a2 -3");
+        assert_eq!(seed.text, "123");
+
+        let share_code = text_field(TextFieldId::ShareCode, "Ab-_9+/= é");
+        assert_eq!(share_code.text, "Ab-_9");
+    }
+
+    #[test]
+    fn typing_stops_at_the_maximum_length() {
+        let max_length = TextFieldId::Seed.max_length();
+        let mut text_field = text_field(TextFieldId::Seed, &"9".repeat(max_length + 5));
+        assert_eq!(text_field.char_count(), max_length);
+
+        text_field.insert("1");
+        assert_eq!(text_field.char_count(), max_length);
+
+        // Typing over a selection makes room first
+        text_field.select_all();
+        text_field.insert("42");
+        assert_eq!(text_field.text, "42");
+    }
+
+    #[test]
+    fn return_submits_the_text() {
+        let mut text_field = text_field(TextFieldId::ShareCode, "abc");
+
+        assert!(matches!(
+            press(&mut text_field, Scancode::Return),
+            Some(UiEvent::TextSubmitted(TextFieldId::ShareCode))
+        ));
+        assert!(press(&mut text_field, Scancode::Up).is_none());
+    }
 }
@@ -0,0 +1,136 @@
+use std::ops::{Add, Div, Mul, Sub};
+
+use maze_gen::point::MazePoint;
+
+use crate::layout::WindowMazeLayout;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct WindowPoint {
+    pub x: i64,
+    pub y: i64,
+}
+
+impl WindowPoint {
+    pub fn new(x: i64, y: i64) -> Self {
+        Self { x, y }
+    }
+}
+
+impl From<(i64, i64)> for WindowPoint {
+    fn from(value: (i64, i64)) -> Self {
+        Self::new(value.0, value.1)
+    }
+}
+
+impl From<WindowPoint> for (i64, i64) {
+    fn from(value: WindowPoint) -> Self {
+        (value.x, value.y)
+    }
+}
+
+impl From<WindowPoint> for sdl2::rect::Point {
+    fn from(value: WindowPoint) -> Self {
+        Self::new(value.x as i32, value.y as i32)
+    }
+}
+
+impl<T> Add<T> for WindowPoint
+where
+    T: Into<WindowPoint>,
+{
+    type Output = WindowPoint;
+
+    fn add(self, rhs: T) -> Self::Output {
+        let rhs = rhs.into();
+
+        Self::Output::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl<T> Sub<T> for WindowPoint
+where
+    T: Into<WindowPoint>,
+{
+    type Output = WindowPoint;
+
+    fn sub(self, rhs: T) -> Self::Output {
+        let rhs = rhs.into();
+
+        Self::Output::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl<T> Mul<T> for WindowPoint
+where
+    T: Into<WindowPoint>,
+{
+    type Output = WindowPoint;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        let rhs = rhs.into();
+
+        Self::Output::new(self.x * rhs.x, self.y * rhs.y)
+    }
+}
+
+impl Mul<i64> for WindowPoint {
+    type Output = WindowPoint;
+
+    fn mul(self, rhs: i64) -> Self::Output {
+        Self::Output::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl<T> Div<T> for WindowPoint
+where
+    T: Into<WindowPoint>,
+{
+    type Output = WindowPoint;
+
+    fn div(self, rhs: T) -> Self::Output {
+        let rhs = rhs.into();
+
+        Self::Output::new(self.x / rhs.x, self.y / rhs.y)
+    }
+}
+
+impl Div<i64> for WindowPoint {
+    type Output = WindowPoint;
+
+    fn div(self, rhs: i64) -> Self::Output {
+        Self::Output::new(self.x / rhs, self.y / rhs)
+    }
+}
+
+/// Converts between positions in the window and cells of a maze laid out in it
+pub trait Convert<T> {
+    fn convert(self, layout: &WindowMazeLayout) -> T;
+}
+
+/// The top left corner of the cell
+impl Convert<WindowPoint> for MazePoint {
+    fn convert(self, layout: &WindowMazeLayout) -> WindowPoint {
+        WindowPoint::new(
+            layout.cell_x_positions[self.x as usize] as i64,
+            layout.cell_y_positions[self.y as usize] as i64,
+        )
+    }
+}
+
+/// The cell under the point, or the closest one if it's outside the maze
+impl Convert<MazePoint> for WindowPoint {
+    fn convert(self, layout: &WindowMazeLayout) -> MazePoint {
+        MazePoint::new(
+            cell_index(&layout.cell_x_positions, self.x),
+            cell_index(&layout.cell_y_positions, self.y),
+        )
+    }
+}
+
+/// The cell that a position is in along one axis, given the positions of the cells' edges
+fn cell_index(edges: &[i32], position: i64) -> i64 {
+    let cells = edges.len().saturating_sub(1).max(1) as i64;
+    let edges_before = edges.partition_point(|&edge| edge as i64 <= position) as i64;
+
+    (edges_before - 1).clamp(0, cells - 1)
+}